@@ -1,7 +1,8 @@
-use std::{io, iter, mem};
+use std::io;
 use std::io::{BufRead, BufReader};
 use aoc_2023::coordinate::grid::Grid;
-use aoc_2023::graph::CycleInfo;
+use aoc_2023::coordinate::symmetry::D4;
+use aoc_2023::graph::CycleShape;
 
 fn main() {
     let stdin = io::stdin();
@@ -17,26 +18,21 @@ fn process_lines(lines: impl Iterator<Item=String>) -> usize {
     let orig_grid = Grid::try_from_vec_of_vecs(orig_grid).expect("Irregular input");
 
     // let one_spin = rotate_grid_clockwise(
-    //     spin_forever(orig_grid.clone()).take(4).last().unwrap()
+    //     (0..3).fold(rolled(orig_grid.clone()), |grid, _| spin(&grid))
     // );
     // println!("After one spin:");
     // println!("{}", display_grid(&one_spin));
 
-    let possible_cycle = CycleInfo::check_cycle(
-        // Prevent infinite memory
-        spin_forever(orig_grid).take(1000)
-    );
-    println!("Cycle? {:?}", possible_cycle.is_ok());
-    let cycle_info: CycleInfo<Grid<u8>> = possible_cycle.unwrap();
-    println!("{} {}", cycle_info.dist_to_cycle_start(), cycle_info.cycle().len());
-    let idx_within_cycle = (3_999_999_999usize - cycle_info.dist_to_cycle_start())
-        % cycle_info.cycle().len();
-    let first_idx = idx_within_cycle + cycle_info.dist_to_cycle_start();
-    // println!("{idx_within_cycle} {first_idx} {}", first_idx % 4);
-    let mut grid = cycle_info.cycle()[idx_within_cycle].clone();
-    let needed_rotations = 4 - (first_idx % 4);
-    assert_eq!(needed_rotations, 1);
-    grid = grid.rotate_clockwise();
+    let mut start = orig_grid;
+    roll_boulders(&mut start);
+    // Only a couple of grids are held at once, however long the run up to the cycle
+    let shape = CycleShape::brent(start.clone(), spin);
+    println!("{} {}", shape.dist_to_cycle_start(), shape.cycle_len());
+    // The last tilt of the billionth spin. The grid at index i has been rotated clockwise i
+    // times, and the replayed grid is the same however far into the cycle that is.
+    let last_idx = 3_999_999_999;
+    let orientation = D4::rotation(last_idx);
+    let grid = orientation.inverse().apply_grid(shape.replay_nth(start, spin, last_idx));
 
     // for (check_idx, mut check_grid) in cycle_info.cycle().iter().cloned().enumerate() {
     //     check_grid = rotate_grid_clockwise(check_grid);
@@ -47,18 +43,11 @@ fn process_lines(lines: impl Iterator<Item=String>) -> usize {
     north_load(&grid)
 }
 
-fn spin_forever(mut grid: Grid<u8>) -> impl Iterator<Item=Grid<u8>> {
-    iter::from_fn(move || {
-        roll_boulders(&mut grid);
-        let clone = grid.clone();
-        // Can't move grid directly because it's captured by a closure
-        // This became less performant after improved grid...
-        let mut tmp = Grid::full(1, 1, 0);
-        mem::swap(&mut tmp, &mut grid);
-        // I guess the idea is this method could panic and that panic could be caught
-        grid = tmp.rotate_clockwise();
-        Some(clone)
-    })
+/// Rotate and roll the next direction's worth of boulders
+fn spin(grid: &Grid<u8>) -> Grid<u8> {
+    let mut grid = grid.clone().rotate_clockwise();
+    roll_boulders(&mut grid);
+    grid
 }
 
 fn roll_boulders(grid: &mut Grid<u8>) {
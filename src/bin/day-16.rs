@@ -1,11 +1,9 @@
-use std::{io, mem};
-use std::collections::VecDeque;
-use std::fmt::{Display, Formatter, Write};
+use std::io;
 use std::io::{BufRead, BufReader};
 use itertools::Itertools;
 use aoc_2023::coordinate::{Direction, UCoordinate};
+use aoc_2023::coordinate::beam::trace_beam;
 use aoc_2023::coordinate::grid::Grid;
-use aoc_2023::util::CheckedAdd;
 
 fn main() {
     let stdin = io::stdin();
@@ -18,107 +16,19 @@ fn main() {
     println!("Second part: {}", part_2(&grid));
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
-struct Visited {
-    row: bool,
-    col: bool,
-}
-
-impl Display for Visited {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_char(if self.visited() { '#' } else { '.' })
-    }
-}
-
-#[allow(unused)]
-impl Visited {
-    pub const fn new() -> Self {
-        Self { row: false, col: false }
-    }
-
-    pub const fn row(self) -> bool {
-        self.row
-    }
-
-    pub const fn col(self) -> bool {
-        self.col
-    }
-
-    pub const fn visited(self) -> bool {
-        self.row || self.col
-    }
-
-    pub fn visit_row(&mut self) -> bool {
-        mem::replace(&mut self.row, true)
-    }
-
-    pub fn visit_col(&mut self) -> bool {
-        mem::replace(&mut self.col, true)
-    }
-}
-
 fn get_entry_point_energy(grid: &Grid<u8>, coordinate: UCoordinate<2>, direction: Direction) -> usize {
-    let mut progress = Grid::full(grid.rows(), grid.cols(), Visited::new());
-
-    // Could have just been a Vec
-    let mut operations = VecDeque::new();
-    operations.push_back((coordinate, direction));
-
-    while let Some((coordinate, direction)) = operations.pop_front() {
-        let visited = &mut progress[coordinate];
-        let c = grid[coordinate] as char;
-        let is_mirror = c == '/' || c == '\\';
-        match direction {
-            Direction::North | Direction::South => if visited.visit_col() && !is_mirror {
-                continue
-            },
-            Direction::East | Direction::West => if visited.visit_row() && !is_mirror {
-                continue
-            },
+    trace_beam(grid, coordinate, direction, |c, direction| {
+        match (*c as char, direction) {
+            ('.', _) => vec![direction],
+            ('/', _) => vec![direction.transpose_secondary()],
+            ('\\', _) => vec![direction.transpose()],
+            ('-', Direction::East | Direction::West) => vec![direction],
+            ('-', Direction::North | Direction::South) => vec![Direction::East, Direction::West],
+            ('|', Direction::North | Direction::South) => vec![direction],
+            ('|', Direction::East | Direction::West) => vec![Direction::North, Direction::South],
+            (c, _) => panic!("Unknown char {c}"),
         }
-
-        let mut add_operation = |dir: Direction| {
-            let Some(mut next) = coordinate.checked_add(&dir) else {
-                return;
-            };
-            grid.bound_coordinate(&mut next);
-            if next != coordinate {
-                // println!("{coordinate:?} -> ({dir:?}) {next:?}");
-                operations.push_back((next, dir));
-            }
-        };
-
-        let new_direction = match c {
-            '.' => direction,
-            '/' => direction.transpose_secondary(),
-            '\\' => direction.transpose(),
-            '-' => {
-                match direction {
-                    Direction::East | Direction::West => direction,
-                    Direction::North | Direction::South => {
-                        add_operation(Direction::East);
-                        Direction::West
-                    }
-                }
-            },
-            '|' => {
-                match direction {
-                    Direction::North | Direction::South => direction,
-                    Direction::East | Direction::West => {
-                        add_operation(Direction::North);
-                        Direction::South
-                    }
-                }
-            },
-            _ => panic!("Unknown char {c}"),
-        };
-        add_operation(new_direction);
-    }
-    // print_grid(&progress);
-    progress.iter_rows()
-        .flat_map(|row| row.iter().copied())
-        .filter(|v| v.visited())
-        .count()
+    }).energized_count()
 }
 
 fn part_1(grid: &Grid<u8>) -> usize {
@@ -2,7 +2,8 @@ use std::{io, str};
 use std::io::{BufRead, BufReader};
 use itertools::Itertools;
 use regex::Regex;
-use aoc_2023::coordinate::{Direction, ICoordinate, twice_shoelace};
+use aoc_2023::coordinate::{Direction, DirectionNotation};
+use aoc_2023::coordinate::polygon::Polygon;
 
 fn main() {
     let stdin = io::stdin();
@@ -15,23 +16,10 @@ fn main() {
     println!("Second part: {}", compute_covered(&plans_2));
 }
 
-type Point = ICoordinate<2>;
-
 fn compute_covered(plans: &[Plan]) -> usize {
-    let boundary: usize = plans.iter().map(|plan| plan.length).sum();
-    let mut current = Point::origin();
-    let twice_area = twice_shoelace(plans.iter()
-        .map(move |plan| {
-            let mut trench: Point = plan.direction.into();
-            for x in trench.as_mut().iter_mut() {
-                *x *= plan.length as isize;
-            }
-            current = current + trench;
-            current
-        }));
-    let diff = twice_area.checked_sub(boundary).expect("Formula issue");
-    assert_eq!(diff % 2, 0, "math issue");
-    (diff / 2 + 1) + boundary
+    Polygon::try_from_steps(plans.iter().map(|plan| (plan.direction, plan.length)))
+        .expect("Trench is not closed")
+        .covered_cells()
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -44,13 +32,8 @@ impl Plan {
     pub fn parse_1(s: &str) -> Self {
         let re = Regex::new(r"^([RLUD]) (\d+) \(#[0-9a-f]{6}\)$").unwrap();
         let (_, [dir, len]) = re.captures(s).expect("input issue").extract();
-        let direction = match dir {
-            "R" => Direction::East,
-            "L" => Direction::West,
-            "U" => Direction::North,
-            "D" => Direction::South,
-            _ => unreachable!(),
-        };
+        let direction = Direction::parse_with(dir.chars().next().unwrap(), DirectionNotation::Urdl)
+            .unwrap();
         let length: usize = len.parse().unwrap();
 
         Self { direction, length }
@@ -59,13 +42,8 @@ impl Plan {
     pub fn parse_2(s: &str) -> Self {
         let re = Regex::new(r"^[RLUD] \d+ \(#([0-9a-f]{5})([0-9a-f])\)$").unwrap();
         let (_, [len, dir]) = re.captures(s).expect("input issue").extract();
-        let direction = match dir {
-            "0" => Direction::East,
-            "2" => Direction::West,
-            "3" => Direction::North,
-            "1" => Direction::South,
-            _ => unreachable!(),
-        };
+        let direction = Direction::parse_with(dir.chars().next().unwrap(), DirectionNotation::AocHex)
+            .unwrap();
         let length: usize = usize::from_str_radix(len, 16).unwrap();
 
         Self { direction, length }
@@ -1,11 +1,12 @@
-use std::{io, iter, str};
+use std::{env, io, iter, str};
 use std::collections::{HashMap, VecDeque};
 use std::hash::Hash;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 use std::str::FromStr;
 use anyhow::{anyhow, bail};
 use itertools::Itertools;
-use aoc_2023::graph::CycleInfo;
+use aoc_2023::graph::{fingerprint, CycleInfo, Graph};
+use aoc_2023::util::open_output;
 
 fn main() {
     let stdin = io::stdin();
@@ -16,6 +17,15 @@ fn main() {
         .map(|x| x.parse())
         .try_collect().expect("parse issue");
 
+    // `--dot [PATH]` draws the module wiring instead of solving
+    let mut args = env::args().skip(1);
+    if args.next().as_deref() == Some("--dot") {
+        let mut out = open_output(args.next().as_deref()).expect("can't open output");
+        write_dot(&module_specs, &mut out).expect("can't write DOT");
+        out.flush().expect("can't write DOT");
+        return;
+    }
+
     println!("First part: {}", part_1(&module_specs));
 
     // println!("First part: {result} ({elapsed:.2?})");
@@ -79,44 +89,30 @@ fn part_1(specs: &[ModuleSpec]) -> usize {
             .collect(),
     };
     let broadcaster_idx = name_idx.get("broadcaster").copied().expect("no broadcaster");
-    // In my puzzle only dg outputs to rx, and it only outputs to rx
-    let dg_idx = name_idx.get("dg").copied().unwrap();
+    // rx has a single input, fed by independent counters; report them rather than assume the wiring
+    if let Some(feeder) = find_counters(specs) {
+        println!("rx is fed by {feeder}");
+    }
 
-    let mut low_sent = 0usize;
-    let mut high_sent = 0usize;
-    let cycle = CycleInfo::check_cycle(
-        iter::repeat_with(|| {
-            let ret = full_state.clone();
-            let (low, high) = push_button(
-                &mut full_state, &modules, &input_maps, broadcaster_idx, dg_idx);
-            low_sent += low;
-            high_sent += high;
-            (ret, low, high)
-        }).take(1000)
-    );
-    if let Ok(cycle) = cycle {
-        low_sent -= cycle.cycle().first().unwrap().1;
-        high_sent -= cycle.cycle().first().unwrap().2;
-        println!("Cycle info: {} {}", cycle.dist_to_cycle_start(), cycle.cycle().len());
-        println!("So far: {low_sent} {high_sent}");
-        assert!(cycle.dist_to_cycle_start() + cycle.cycle().len() <= 1000);
-        let left_to_go = 1000 - cycle.dist_to_cycle_start() - cycle.cycle().len();
-        let cycles_to_go = left_to_go / cycle.cycle().len();
-        let remainder = left_to_go % cycle.cycle().len();
-        for (idx, (_, low, high)) in cycle.cycle().iter().enumerate() {
-            low_sent += low * cycles_to_go;
-            high_sent += high * cycles_to_go;
-            if idx < remainder {
-                low_sent += low;
-                high_sent += high;
-            }
-        }
-        println!("Total: {low_sent} {high_sent}");
-        println!("First part: {}", low_sent * high_sent);
-    } else {
-        println!("No cycle! Returning so far: {low_sent} {high_sent}.");
-        println!("First part: {}", low_sent * high_sent);
+    // Only a fingerprint of each state is kept rather than a clone of every flip-flop and memory
+    let presses = iter::repeat_with(|| {
+        let ret = fingerprint(&full_state);
+        let (low, high) = push_button(
+            &mut full_state, &modules, &input_maps, broadcaster_idx);
+        (ret, low, high)
+    }).take(1000);
+    let (low_sent, high_sent) = match CycleInfo::check_cycle_by_key(presses, |(state, _, _)| *state) {
+        Ok(cycle) => {
+            println!("Cycle info: {} {}", cycle.dist_to_cycle_start(), cycle.cycle().len());
+            (cycle.sum_over(1000, |(_, low, _)| *low), cycle.sum_over(1000, |(_, _, high)| *high))
+        },
+        Err(presses) => {
+            println!("No cycle!");
+            presses.iter().fold((0, 0), |(low_sent, high_sent), (_, low, high)| (low_sent + low, high_sent + high))
+        },
     };
+    println!("Total: {low_sent} {high_sent}");
+    println!("First part: {}", low_sent * high_sent);
 
 
 
@@ -135,9 +131,56 @@ fn part_1(specs: &[ModuleSpec]) -> usize {
     0
 }
 
+fn module_graph(specs: &[ModuleSpec]) -> Graph<&str, ()> {
+    let mut network = Graph::new_directed();
+    for spec in specs {
+        network.add_node(spec.name.as_str());
+        for dest in spec.destinations.iter() {
+            network.add_edge(spec.name.as_str(), dest.as_str(), ());
+        }
+    }
+    network
+}
+
+/// The broadcaster is a double circle, flip-flops are boxes, conjunctions diamonds and outputs
+/// without a module plain text
+fn write_dot(specs: &[ModuleSpec], out: &mut impl Write) -> io::Result<()> {
+    let kinds: HashMap<_, _> = specs.iter().map(|spec| (spec.name.as_str(), spec.kind)).collect();
+    module_graph(specs).write_dot(
+        out,
+        |_, name| {
+            let shape = match kinds.get(name) {
+                Some(ModuleKind::Broadcaster) => "doublecircle",
+                Some(ModuleKind::FlipFlop) => "box",
+                Some(ModuleKind::Conjunction) => "diamond",
+                None => "plaintext",
+            };
+            vec![("label", name.to_string()), ("shape", shape.into())]
+        },
+        |_, _| vec![],
+    )
+}
+
+/// Prints the sub-circuits feeding the module that feeds rx and returns that module's name, or
+/// None unless exactly one module outputs to rx
+fn find_counters(specs: &[ModuleSpec]) -> Option<&str> {
+    let network = module_graph(specs);
+    let rx = network.node_id(&"rx")?;
+    let feeder = network.predecessors(rx).exactly_one().ok()?;
+    let components = network.strongly_connected_components();
+    for (input, nodes) in network.feeding_subgraphs(feeder) {
+        let largest = nodes.iter()
+            .map(|node| components.members(components.component_of(*node)).len())
+            .max().unwrap_or(0);
+        println!("Counter feeding {} via {}: {} modules, largest loop {largest}",
+                 network.label(feeder), network.label(input), nodes.len());
+    }
+    Some(network.label(feeder))
+}
+
 fn push_button(full_state: &mut FullState, modules: &[Module],
                input_maps: &Vec<HashMap<usize, usize>>,
-               broadcaster_idx: usize, _dg_idx: usize) -> (usize, usize) {
+               broadcaster_idx: usize) -> (usize, usize) {
     let empty_map: HashMap<usize, usize> = HashMap::new();
     // let mut rx_sends = 0usize;
 
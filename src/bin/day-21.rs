@@ -1,8 +1,7 @@
-use std::mem;
-use std::collections::HashMap;
 use std::error::Error;
 use anyhow::anyhow;
-use aoc_2023::coordinate::UCoordinate;
+use aoc_2023::coordinate::{Direction, UCoordinate};
+use aoc_2023::coordinate::bit_grid::BitGrid;
 use aoc_2023::coordinate::grid::{get_byte_grid_from_stdin, Grid};
 
 type Point = UCoordinate<2>;
@@ -21,31 +20,16 @@ fn main() -> Result<(), Box<dyn Error>> {
 const STEPS: usize = 64;
 
 fn part_1(grid: &Grid<u8>, starting_position: Point) -> usize {
-    let mut distances = HashMap::new();
-    distances.insert(starting_position, 0usize);
-
-    let mut next_distance = 1usize;
-    let mut frontier = vec![starting_position];
-    let mut next_frontier = vec![];
-    while next_distance <= STEPS {
-        for point in frontier.iter().copied() {
-            for (_, neighbor) in grid.neighbors(&point) {
-                if grid[neighbor] as char == '#' { continue; }
-                let current = distances.entry(neighbor).or_insert(usize::MAX);
-                if next_distance >= *current { continue; }
-                *current = next_distance;
-                next_frontier.push(neighbor);
-            }
+    let open = BitGrid::from_grid(grid, |b| *b as char != '#');
+    let mut reached = BitGrid::new(grid.rows(), grid.cols());
+    reached.insert(starting_position);
+    for _ in 0..STEPS {
+        let mut next = BitGrid::new(grid.rows(), grid.cols());
+        for direction in Direction::ALL {
+            next |= &reached.shifted(direction, 1);
         }
-        mem::swap(&mut frontier, &mut next_frontier);
-        next_frontier.clear();
-        next_distance += 1;
+        next &= &open;
+        reached = next;
     }
-    grid.iter_idxs()
-        .filter(|idx| {
-            distances.get(idx).copied()
-                .map(|dist| dist <= STEPS && dist % 2 == 0)
-                .unwrap_or(false)
-        })
-        .count()
+    reached.count_ones()
 }
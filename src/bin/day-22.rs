@@ -1,4 +1,4 @@
-use std::{iter, str};
+use std::str;
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::error::Error;
@@ -6,20 +6,18 @@ use std::hash::Hash;
 use std::str::FromStr;
 use anyhow::anyhow;
 use itertools::Itertools;
-use aoc_2023::coordinate::{UCoordinate};
+use aoc_2023::coordinate::UCoordinate;
+use aoc_2023::coordinate::aa_box::AaBox;
+use aoc_2023::coordinate::projection::XY;
+use aoc_2023::graph::Graph;
 use aoc_2023::util::{FromStrParser, get_lines_from_stdin, Parser};
 
 type Point = UCoordinate<3>;
 type Point2 = UCoordinate<2>;
 
-// Useful in the future - ranges for Coordinate<N> that represent N-dimensional spaces
-// Maybe a typed projection mechanism for both coordinates and these ranges?
-
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 struct Block {
-    start: Point,
-    end: Point,
-    axis: usize,
+    extent: AaBox<Point>,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -44,39 +42,35 @@ fn parts(blocks: &[Block]) -> () {
 
     let sections = sections;
 
-    let mut above: HashMap<usize, HashSet<usize>> = HashMap::new();
-    let mut below: HashMap<usize, HashSet<usize>> = HashMap::new();
-    let mut zero_below: HashSet<_> = (0..blocks.len()).into_iter().collect();
-    let mut below_count: HashMap<usize, usize> = HashMap::new();
-
+    // Edges point from a block to the ones directly above it; node ids match block indices
+    let mut supports = Graph::new_directed();
+    for idx in 0..blocks.len() {
+        supports.add_node(idx);
+    }
+    let mut linked = HashSet::new();
     for seq in sections.values() {
         for ((_, low), (_, high)) in seq.iter().copied().tuple_windows() {
-            above.entry(low).or_default().insert(high);
-            if below.entry(high).or_default().insert(low) {
-                *below_count.entry(high).or_default() += 1;
+            if linked.insert((low, high)) {
+                supports.add_edge_between(low, high, ());
             }
-            zero_below.remove(&high);
         }
     }
 
-    let above = above;
-    let below = below;
+    let supports = supports;
     let mut can_destroy = HashSet::new();
     let mut moved_blocks: HashMap<usize, Block> = HashMap::new();
 
-    let empty: HashSet<usize> = HashSet::new();
-
     let mut moved_count = 0usize;
 
-    while let Some(idx) = zero_below.iter().copied().next() {
+    // Everything in a layer rests only on blocks from earlier layers, so they can fall in order
+    let layers = supports.topological_layers().expect("blocks cannot support each other");
+    for idx in layers.into_iter().flatten() {
         let mut block = blocks[idx];
-        zero_below.remove(&idx);
-        assert_eq!(below_count.get(&idx).copied().unwrap_or_default(), 0);
 
         let mut max_top = 0usize;
         let mut tied = false;
         let mut support = usize::MAX;
-        for below_idx in below.get(&idx).unwrap_or(&empty).iter().copied() {
+        for below_idx in supports.predecessors(idx) {
             let challenger = moved_blocks.get(&below_idx).expect("logic error").top();
             if support == usize::MAX {
                 support = below_idx;
@@ -101,14 +95,6 @@ fn parts(blocks: &[Block]) -> () {
         }
         can_destroy.insert(idx);
 
-        for above_idx in above.get(&idx).unwrap_or(&empty) {
-            let count = below_count.get_mut(&above_idx).unwrap();
-            *count = count.checked_sub(1).expect("logic error");
-            if *count == 0 {
-                assert!(zero_below.insert(*above_idx));
-            }
-        }
-
         moved_count += 1;
     }
 
@@ -126,7 +112,7 @@ fn parts(blocks: &[Block]) -> () {
 
         moved_blocks.remove(&idx);
         let mut to_check = BinaryHeap::new();
-        to_check.extend(above.get(&idx).unwrap_or(&empty).iter().copied().map(|idx| {
+        to_check.extend(supports.successors(idx).map(|idx| {
             let bottom = moved_blocks.get(&idx).unwrap().bottom() as isize;
             (-bottom, idx)
         }));
@@ -138,7 +124,7 @@ fn parts(blocks: &[Block]) -> () {
                 assert!((-entered_bottom as usize) > bottom);
                 continue;
             }
-            let max_top = below.get(&check).unwrap_or(&empty).iter().copied()
+            let max_top = supports.predecessors(check)
                 .map(|below_idx| moved_blocks.get(&below_idx).map(|block| block.top()).unwrap_or(0))
                 .max().unwrap_or(0);
             let movement = bottom.saturating_sub(max_top + 1);
@@ -148,7 +134,7 @@ fn parts(blocks: &[Block]) -> () {
             sum += 1;
             let block = moved_blocks.get_mut(&check).unwrap();
             block.lower(movement);
-            to_check.extend(above.get(&check).unwrap_or(&empty).iter().copied().map(|idx| {
+            to_check.extend(supports.successors(check).map(|idx| {
                 let bottom = moved_blocks.get(&idx).unwrap().bottom() as isize;
                 (-bottom, idx)
             }));
@@ -159,70 +145,29 @@ fn parts(blocks: &[Block]) -> () {
 }
 
 impl Block {
-    pub fn shadow(&self) -> Box<dyn Iterator<Item=Point2> + '_> {
-        if self.axis == 2 {
-            Box::new(iter::once((self.start.as_ref()[0], self.start.as_ref()[1]).into()))
-        } else {
-            let mut shadow: Point2 = [self.start.as_ref()[0], self.start.as_ref()[1]].into();
-            let increment: Point2 = if self.axis == 0 { [1, 0].into() } else { [0, 1].into() };
-            Box::new((0..=(self.end.as_ref()[self.axis] - self.start.as_ref()[self.axis]))
-                .into_iter()
-                .map(move |_| {
-                    let result = shadow;
-                    shadow = shadow + increment;
-                    result
-                })
-            )
-        }
+    pub fn shadow(&self) -> impl Iterator<Item=Point2> {
+        XY.project(&self.extent).iter()
     }
 
     pub fn lower(&mut self, distance: usize) -> &mut Self {
-        let start_z = &mut self.start.as_mut()[2];
-        *start_z = start_z.checked_sub(distance).expect("logic error");
-        let end_z = &mut self.end.as_mut()[2];
-        *end_z = end_z.checked_sub(distance).expect("logic error");
+        self.extent = self.extent.translated_along(2, -(distance as i128)).expect("logic error");
         self
     }
 
     pub fn bottom(&self) -> usize {
-        self.start.as_ref()[2]
+        self.extent.start().as_ref()[2]
     }
 
     pub fn top(&self) -> usize {
-        self.end.as_ref()[2]
+        self.extent.end().as_ref()[2]
     }
 
     pub fn try_new(a: Point, b: Point) -> Result<Self, anyhow::Error> {
-        if a == b {
-            return Ok(Self { start: a, end: b, axis: 2 })
-        }
-        if a.as_ref()[0] != b.as_ref()[0] {
-            if a.as_ref()[1..] == b.as_ref()[1..] {
-                let start = a.min(b);
-                let end = a.max(b);
-                Ok(Self { start, end, axis: 0 })
-            } else {
-                Err(anyhow!("Not 1-D {a:?} {b:?}"))
-            }
-        } else if a.as_ref()[1] != b.as_ref()[1] {
-            if a.as_ref()[0] == b.as_ref()[0] && a.as_ref()[2] == b.as_ref()[2] {
-                let start = a.min(b);
-                let end = a.max(b);
-                Ok(Self { start, end, axis: 1 })
-            } else {
-                Err(anyhow!("Not 1-D {a:?} {b:?}"))
-            }
-        } else if a.as_ref()[2] != b.as_ref()[2] {
-            if a.as_ref()[..2] == b.as_ref()[..2] {
-                let start = a.min(b);
-                let end = a.max(b);
-                Ok(Self { start, end, axis: 2 })
-            } else {
-                Err(anyhow!("Not 1-D {a:?} {b:?}"))
-            }
-        } else {
-            Err(anyhow!("Not 1-D {a:?} {b:?}"))
+        let differing_axes = a.iter().zip(b.iter()).filter(|(a, b)| a != b).count();
+        if differing_axes > 1 {
+            return Err(anyhow!("Not 1-D {a:?} {b:?}"));
         }
+        Ok(Self { extent: AaBox::new(a, b) })
     }
 }
 
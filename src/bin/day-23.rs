@@ -1,35 +1,44 @@
-use std::{iter, str};
+use std::{env, iter, str};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{BinaryHeap, HashSet};
 use std::error::Error;
 use std::hash::Hash;
 use std::str::FromStr;
 use anyhow::{anyhow, bail};
 use itertools::Itertools;
-use topological_sort::TopologicalSort;
-use aoc_2023::coordinate::Direction;
-use aoc_2023::coordinate::grid::{get_byte_grid_from_stdin, Point};
-use aoc_2023::util::{CheckedSub, FromStrParser, get_lines_from_stdin, Parser};
+use aoc_2023::coordinate::{Direction, DirectionNotation};
+use aoc_2023::coordinate::grid::{get_byte_grid_from_stdin, Grid, Point};
+use aoc_2023::graph::Graph;
+use aoc_2023::util::{CheckedSub, FromStrParser, get_lines_from_stdin, open_output, Parser};
 
 fn convert_to_direction(b: u8) -> Result<Option<Direction>, anyhow::Error> {
     match b as char {
         '#' => Ok(None),
         '.' => Err(anyhow!("DAG invariant violated")),
-        '>' => Ok(Some(Direction::East)),
-        'v' => Ok(Some(Direction::South)),
-        '^' => Ok(Some(Direction::North)),
-        '<' => Ok(Some(Direction::West)),
-        _ => Err(anyhow!("Not recognized: {b:?}")),
+        c => DirectionNotation::Arrow.parse(c)
+            .map(Some)
+            .map_err(|_| anyhow!("Not recognized: {b:?}")),
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let grid = get_byte_grid_from_stdin()?;
+    // `--dot [PATH]` draws the junction graph instead of solving
+    let mut args = env::args().skip(1);
+    if args.next().as_deref() == Some("--dot") {
+        let mut out = open_output(args.next().as_deref())?;
+        junction_graph(&grid).write_dot(
+            &mut out,
+            |_, point| vec![("label", format!("{},{}", point[0], point[1]))],
+            |_, edge| vec![("label", edge.weight.to_string())],
+        )?;
+        out.flush()?;
+        return Ok(());
+    }
     let is_path = |_: &Point, b: &u8| *b as char != '#';
     let source: Point = (0, 1).into();
 
-    let mut graph = HashMap::new();
-    let mut sort: TopologicalSort<Point> = TopologicalSort::new();
+    let mut graph = Graph::new_directed();
 
     {
         // Build graph
@@ -40,8 +49,7 @@ fn main() -> Result<(), Box<dyn Error>> {
             let (dest, dest_direction, length) = grid.follow_path(&next_source, next_direction, is_path);
             // println!("{next_source:?} -> {dest:?} {dest_direction:?} {length}");
             let graph_source = next_source.checked_sub(&next_direction).unwrap();
-            graph.entry(dest).or_insert(vec![]).push((graph_source, next_direction, length + 1, dest_direction));
-            sort.add_dependency(graph_source, dest);
+            graph.add_edge(graph_source, dest, length + 1);
             for (dir, neighbor) in grid.neighbors(&dest) {
                 if dir.opposite() == dest_direction { continue; }
                 if convert_to_direction(grid[neighbor])? == Some(dir) && !explored.contains(&(neighbor, dir)) {
@@ -52,37 +60,62 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
 
     let graph = graph;
-    let mut max_distances = HashMap::new();
-    max_distances.insert(source, 0usize);
-    while let Some(point) = sort.pop() {
-        let Some(v) = graph.get(&point) else {
-            continue;  // Inaccessible I think - is that allowed? Also would cover start.
-        };
-        let mut max_distance = 0usize;
-        for (source, _, length, _) in v {
-            let Some(source_dist) = max_distances.get(source) else {
-                continue;
-            };
-            max_distance = max_distance.max(*length + *source_dist);
-        }
-        assert!(max_distances.insert(point, max_distance).is_none());
-    }
+    let max_distances = graph.dag_longest_distances(graph.node_id(&source).expect("source has an exit"))?;
+    let target = graph.node_id(&(grid.rows() - 1, grid.cols() - 2).into());
 
     println!(
         "Part 1: {}",
-        max_distances
-            .get(&(grid.rows() - 1, grid.cols() - 2).into())
+        target.and_then(|target| max_distances[target])
             .ok_or(anyhow!("No path to endpoint"))?
     );
+    println!("Part 2: {}", part_2(&grid).ok_or(anyhow!("No path to endpoint"))?);
     Ok(())
 }
 
-fn parts() {
-   todo!()
+/// Slopes count as plain path, so the junction graph is undirected and has cycles
+fn junction_graph(grid: &Grid<u8>) -> Graph<Point, usize> {
+    Graph::junctions_from_grid(grid, |_, b| *b as char != '#')
+}
+
+fn part_2(grid: &Grid<u8>) -> Option<usize> {
+    let graph = junction_graph(grid);
+    let source = graph.node_id(&(0, 1).into())?;
+    let target = graph.node_id(&(grid.rows() - 1, grid.cols() - 2).into())?;
+    graph.longest_simple_path(source, target).map(|longest| longest.length)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_part_2() {
+        let grid: Grid<u8> = "\
+#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#".lines()
+            .map(|s| s.as_bytes().to_vec())
+            .collect::<Vec<_>>().try_into().unwrap();
+        assert_eq!(part_2(&grid), Some(154));
+    }
 }
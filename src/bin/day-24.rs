@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use anyhow::anyhow;
+use itertools::Itertools;
+use aoc_2023::coordinate::ICoordinate;
+use aoc_2023::coordinate::line::Trajectory;
+use aoc_2023::util::{FromStrParser, get_lines_from_stdin, Parser};
+
+const TEST_AREA: RangeInclusive<i128> = 200_000_000_000_000..=400_000_000_000_000;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let lines = get_lines_from_stdin()?;
+    let hailstones = FromStrParser::<Hailstone>::new()
+        .parse_lines_to_vec(lines.iter().map(|s| s.as_str()))?;
+    println!("Part 1: {}", part_1(&hailstones, &TEST_AREA));
+    Ok(())
+}
+
+fn part_1(hailstones: &[Hailstone], area: &RangeInclusive<i128>) -> usize {
+    hailstones.iter()
+        .map(|h| h.0.project_xy())
+        .tuple_combinations()
+        .filter(|(a, b)| {
+            a.intersect(b).crossing()
+                .map(|crossing| crossing.in_future_for_both() && crossing.within(area))
+                .unwrap_or(false)
+        })
+        .count()
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+struct Hailstone(Trajectory<3>);
+
+impl FromStr for Hailstone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (position, velocity) = s.split_once('@').ok_or(anyhow!("No '@'"))?;
+        let parse = |s: &str| -> Result<ICoordinate<3>, anyhow::Error> {
+            let (x, y, z) = s.split(',')
+                .map(|x| x.trim().parse::<isize>())
+                .collect_tuple().ok_or(anyhow!("Expected three components in {s:?}"))?;
+            Ok(ICoordinate::new([x?, y?, z?]))
+        };
+        Ok(Hailstone(Trajectory::new(parse(position)?, parse(velocity)?)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part_1() {
+        let hailstones = FromStrParser::<Hailstone>::new().parse_lines_to_vec("\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3".lines()).unwrap();
+        assert_eq!(part_1(&hailstones, &(7..=27)), 2);
+    }
+}
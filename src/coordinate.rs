@@ -1,8 +1,22 @@
+pub mod aa_box;
+pub mod beam;
+pub mod bit_grid;
 pub mod grid;
-
-use std::ops::{Add, Bound, Deref, DerefMut, RangeBounds, Sub};
+pub mod grid_n;
+pub mod hex;
+pub mod line;
+pub mod polygon;
+pub mod projection;
+pub mod shapes;
+pub mod symmetry;
+
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::hash::Hash;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Bound, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Neg, RangeBounds, Sub, SubAssign};
+use std::str::FromStr;
 use itertools::Itertools;
-use crate::util::{CheckedAdd, CheckedSub};
+use crate::util::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum Direction {
@@ -12,14 +26,9 @@ pub enum Direction {
     West,
 }
 
-impl From<Direction> for ICoordinate<2> {
+impl<T: SignedScalar> From<Direction> for Coordinate<T, 2> {
     fn from(value: Direction) -> Self {
-        match value {
-            Direction::North => ICoordinate([-1, 0]),
-            Direction::East => ICoordinate([0, 1]),
-            Direction::South => ICoordinate([1, 0]),
-            Direction::West => ICoordinate([0, -1]),
-        }
+        Coordinate::origin() + value
     }
 }
 
@@ -88,155 +97,256 @@ impl Direction {
             Direction::West => Direction::South,
         }
     }
-}
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
-pub struct ICoordinate<const N: usize>([isize; N]);
+    pub const fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Straight => self,
+            Turn::Right => self.rotate_clockwise(),
+            Turn::Back => self.opposite(),
+            Turn::Left => self.rotate_counter_clockwise(),
+        }
+    }
 
-impl<const N: usize> ICoordinate<N> {
-    pub const fn new(data: [isize; N]) -> Self {
-        assert!(N > 0);
-        Self(data)
+    /// The turn that takes this heading to `other`
+    pub const fn turn_to(self, other: Self) -> Turn {
+        Turn::ALL[(other as usize + 4 - self as usize) % 4]
     }
 
-    pub const fn origin() -> Self {
-        assert!(N > 0);
-        Self([0; N])
+    pub fn parse_with(c: char, notation: DirectionNotation) -> Result<Self, DirectionParseError> {
+        notation.parse(c)
     }
 
-    pub fn taxicab_dist(&self, other: &Self) -> usize {
-        self.0.iter().copied()
-            .zip(other.0.iter().copied())
-            .map(|(a, b)| a.abs_diff(b))
-            .sum()
+    pub const fn display(self, notation: DirectionNotation) -> NotatedDirection {
+        NotatedDirection(self, notation)
     }
+}
 
-    pub fn king_dist(&self, other: &Self) -> usize {
-        // In 2D this is number of moves for a chess king to get from self to other
-        self.0.iter().copied()
-            .zip(other.0.iter().copied())
-            .map(|(a, b)| a.abs_diff(b))
-            .max().expect("Invariant violated")
+/// Single-character spellings of `Direction` used by puzzle inputs. The letters and symbols don't
+/// overlap between notations, so `TryFrom<char>` accepts any of them.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum DirectionNotation {
+    /// N, E, S, W
+    Compass,
+    /// U, R, D, L
+    Urdl,
+    /// ^, >, v, <
+    Arrow,
+    /// 0 = R, 1 = D, 2 = L, 3 = U, as in the hex-encoded instructions of day 18
+    AocHex,
+}
+
+impl DirectionNotation {
+    pub const ALL: [DirectionNotation; 4] = [
+        DirectionNotation::Compass, DirectionNotation::Urdl, DirectionNotation::Arrow,
+        DirectionNotation::AocHex,
+    ];
+
+    /// Characters for North, East, South, West in that order
+    const fn chars(self) -> [char; 4] {
+        match self {
+            DirectionNotation::Compass => ['N', 'E', 'S', 'W'],
+            DirectionNotation::Urdl => ['U', 'R', 'D', 'L'],
+            DirectionNotation::Arrow => ['^', '>', 'v', '<'],
+            DirectionNotation::AocHex => ['3', '0', '1', '2'],
+        }
     }
 
-    /// Requires that the range contain at least one element - if so returns Ok(new value), or else
-    /// returns Err(existing value)
-    pub fn bound_axis(&mut self, axis: usize, bound: impl RangeBounds<isize>) -> Result<isize, isize> {
-        let target = &mut self.0[axis];
-        let start = match bound.start_bound() {
-            Bound::Excluded(s) => Bound::Included(s.checked_add(1).ok_or(*target)?),
-            b => b.cloned(),
-        };
-        let end = match bound.end_bound() {
-            Bound::Excluded(e) => Bound::Included(e.checked_sub(1).ok_or(*target)?),
-            b => b.cloned(),
-        };
-        match (start, end) {
-            (Bound::Included(s), Bound::Included(e)) => if e < s {
-                return Err(*target);
-            },
-            _ => (),
-        };
-        match start {
-            Bound::Excluded(_) => unreachable!(),
-            Bound::Included(s) => if *target < s {
-                *target = s;
-            },
-            Bound::Unbounded => (),
-        };
-        match end {
-            Bound::Excluded(_) => unreachable!(),
-            Bound::Included(e) => if *target > e {
-                *target = e;
-            },
-            Bound::Unbounded => (),
-        };
-        Ok(*target)
+    pub fn parse(self, c: char) -> Result<Direction, DirectionParseError> {
+        self.chars().iter()
+            .position(|x| *x == c)
+            .map(|idx| Direction::ALL[idx])
+            .ok_or(DirectionParseError::Unrecognized(c))
     }
-}
 
-impl<const N: usize> Deref for ICoordinate<N> {
-    type Target = [isize; N];
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    pub const fn to_char(self, direction: Direction) -> char {
+        self.chars()[direction as usize]
     }
 }
 
-impl<const N: usize> DerefMut for ICoordinate<N> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum DirectionParseError {
+    #[error("unrecognized direction {0:?}")]
+    Unrecognized(char),
+    #[error("expected a single character, got {0:?}")]
+    NotOneChar(String),
+}
+
+impl TryFrom<char> for Direction {
+    type Error = DirectionParseError;
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        DirectionNotation::ALL.iter()
+            .find_map(|notation| notation.parse(value).ok())
+            .ok_or(DirectionParseError::Unrecognized(value))
     }
 }
 
-impl<const N: usize> Default for ICoordinate<N> {
-    fn default() -> Self {
-        Self::origin()
+impl FromStr for Direction {
+    type Err = DirectionParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars().exactly_one()
+            .map_err(|_| DirectionParseError::NotOneChar(s.to_owned()))?
+            .try_into()
     }
 }
 
-impl<const N: usize> From<[isize; N]> for ICoordinate<N> {
-    fn from(value: [isize; N]) -> Self {
-        Self::new(value)
+/// Uses `DirectionNotation::Compass`; see `Direction::display` for the others
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.display(DirectionNotation::Compass), f)
     }
 }
 
-impl<const N: usize> From<ICoordinate<N>> for [isize; N] {
-    fn from(value: ICoordinate<N>) -> Self {
-        value.0
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct NotatedDirection(Direction, DirectionNotation);
+
+impl Display for NotatedDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char(self.1.to_char(self.0))
     }
 }
 
-impl<const N: usize> Add<ICoordinate<N>> for ICoordinate<N> {
-    type Output = Self;
-    fn add(self, rhs: ICoordinate<N>) -> Self::Output {
-        let mut result = self.0.clone();
-        for (target, to_add) in result.iter_mut().zip_eq(rhs.0.iter().copied()) {
-            *target += to_add;
+/// A turn relative to the current heading
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Turn {
+    Straight,
+    Right,
+    Back,
+    Left,
+}
+
+impl Turn {
+    pub const ALL: [Turn; 4] = [Turn::Straight, Turn::Right, Turn::Back, Turn::Left];
+
+    pub const fn reverse(self) -> Self {
+        match self {
+            Turn::Right => Turn::Left,
+            Turn::Left => Turn::Right,
+            _ => self,
         }
-        Self(result)
     }
 }
 
+/// Primitive integers usable as coordinate components. Every value fits in an `i128`, which is
+/// what overflow-prone and mixed-type arithmetic widens to.
+pub trait Scalar: Copy + Ord + Hash + Debug + Default + Sum
+    + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn to_i128(self) -> i128;
+    fn try_from_i128(value: i128) -> Option<Self>;
+}
+
+pub trait SignedScalar: Scalar + Neg<Output=Self> {
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn try_from_i128(value: i128) -> Option<Self> {
+                value.try_into().ok()
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed_scalar {
+    ($($t:ty),*) => {$(
+        impl SignedScalar for $t {
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+        }
+    )*};
+}
+
+// u128 is left out since it does not fit in i128
+impl_scalar!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+impl_signed_scalar!(i8, i16, i32, i64, i128, isize);
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+pub struct Coordinate<T, const N: usize>([T; N]);
 
-pub struct UCoordinate<const N: usize>([usize; N]);
+pub type ICoordinate<const N: usize> = Coordinate<isize, N>;
+pub type UCoordinate<const N: usize> = Coordinate<usize, N>;
 
-impl<const N: usize> UCoordinate<N> {
-    pub fn new(data: [usize; N]) -> Self {
+impl<T: Scalar, const N: usize> Coordinate<T, N> {
+    pub const fn new(data: [T; N]) -> Self {
         assert!(N > 0);
         Self(data)
     }
 
     pub const fn origin() -> Self {
         assert!(N > 0);
-        Self([0; N])
+        Self([T::ZERO; N])
     }
 
+    /// Panics if the distance does not fit in a `usize`; see `checked_taxicab_dist`
     pub fn taxicab_dist(&self, other: &Self) -> usize {
-        self.0.iter().copied()
+        self.checked_taxicab_dist(other).expect("distance overflows usize")
+    }
+
+    /// None if the distance does not fit in a `usize`
+    pub fn checked_taxicab_dist(&self, other: &Self) -> Option<usize> {
+        let total = self.0.iter().copied()
             .zip(other.0.iter().copied())
-            .map(|(a, b)| a.abs_diff(b))
-            .sum()
+            .try_fold(0u128, |acc, (a, b)| acc.checked_add(abs_diff(a, b)))?;
+        usize::try_from(total).ok()
     }
 
+    /// Panics if the distance does not fit in a `usize`; see `checked_king_dist`
     pub fn king_dist(&self, other: &Self) -> usize {
+        self.checked_king_dist(other).expect("distance overflows usize")
+    }
+
+    /// None if the distance does not fit in a `usize`
+    pub fn checked_king_dist(&self, other: &Self) -> Option<usize> {
         // In 2D this is number of moves for a chess king to get from self to other
-        self.0.iter().copied()
+        let max = self.0.iter().copied()
             .zip(other.0.iter().copied())
-            .map(|(a, b)| a.abs_diff(b))
-            .max().expect("Invariant violated")
+            .map(|(a, b)| abs_diff(a, b))
+            .max().expect("Invariant violated");
+        usize::try_from(max).ok()
     }
 
     /// Requires that the range contain at least one element - if so returns Ok(new value), or else
     /// returns Err(existing value)
-    pub fn bound_axis(&mut self, axis: usize, bound: impl RangeBounds<usize>) -> Result<usize, usize> {
+    pub fn bound_axis(&mut self, axis: usize, bound: impl RangeBounds<T>) -> Result<T, T> {
         let target = &mut self.0[axis];
         let start = match bound.start_bound() {
-            Bound::Excluded(s) => Bound::Included(s.checked_add(1).ok_or(*target)?),
+            Bound::Excluded(s) => Bound::Included(s.checked_add(T::ONE).ok_or(*target)?),
             b => b.cloned(),
         };
         let end = match bound.end_bound() {
-            Bound::Excluded(e) => Bound::Included(e.checked_sub(1).ok_or(*target)?),
+            Bound::Excluded(e) => Bound::Included(e.checked_sub(T::ONE).ok_or(*target)?),
             b => b.cloned(),
         };
         match (start, end) {
@@ -261,195 +371,435 @@ impl<const N: usize> UCoordinate<N> {
         };
         Ok(*target)
     }
+
+    pub fn component_min(&self, other: &Self) -> Self {
+        self.zip_map(other, |a, b| a.min(b))
+    }
+
+    pub fn component_max(&self, other: &Self) -> Self {
+        self.zip_map(other, |a, b| a.max(b))
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.0.iter().zip_eq(other.0.iter())
+            .map(|(a, b)| *a * *b)
+            .sum()
+    }
+
+    /// None if any component is out of range for `U`
+    pub fn try_cast<U: Scalar>(&self) -> Option<Coordinate<U, N>> {
+        let mut result = [U::ZERO; N];
+        for (target, value) in result.iter_mut().zip_eq(self.0.iter()) {
+            *target = U::try_from_i128(value.to_i128())?;
+        }
+        Some(Coordinate(result))
+    }
+
+    /// Lossless conversion to a wider component type
+    pub fn cast<U: Scalar + From<T>>(&self) -> Coordinate<U, N> {
+        Coordinate(self.0.map(U::from))
+    }
+
+    fn zip_map(&self, other: &Self, f: impl Fn(T, T) -> T) -> Self {
+        let mut result = self.0;
+        for (target, value) in result.iter_mut().zip_eq(other.0.iter().copied()) {
+            *target = f(*target, value);
+        }
+        Self(result)
+    }
+
+    fn try_zip_map(&self, other: &Self, f: impl Fn(T, T) -> Option<T>) -> Option<Self> {
+        let mut result = self.0;
+        for (target, value) in result.iter_mut().zip_eq(other.0.iter().copied()) {
+            *target = f(*target, value)?;
+        }
+        Some(Self(result))
+    }
+}
+
+impl<T: SignedScalar, const N: usize> Coordinate<T, N> {
+    pub fn signum(&self) -> Self {
+        Self(self.0.map(|x| x.signum()))
+    }
+}
+
+fn abs_diff<T: Scalar>(a: T, b: T) -> u128 {
+    a.to_i128().abs_diff(b.to_i128())
 }
 
-impl<const N: usize> Default for UCoordinate<N> {
+impl<T, const N: usize> Deref for Coordinate<T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for Coordinate<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Scalar, const N: usize> Default for Coordinate<T, N> {
     fn default() -> Self {
         Self::origin()
     }
 }
 
-impl<const N: usize> From<[usize; N]> for UCoordinate<N> {
-    fn from(value: [usize; N]) -> Self {
+impl<T: Scalar, const N: usize> From<[T; N]> for Coordinate<T, N> {
+    fn from(value: [T; N]) -> Self {
         Self::new(value)
     }
 }
 
-impl<const N: usize> From<UCoordinate<N>> for [usize; N] {
-    fn from(value: UCoordinate<N>) -> Self {
+impl<T, const N: usize> From<Coordinate<T, N>> for [T; N] {
+    fn from(value: Coordinate<T, N>) -> Self {
         value.0
     }
 }
 
-impl<const N: usize> Deref for UCoordinate<N> {
-    type Target = [usize; N];
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl<T: Scalar, const N: usize> Add<Coordinate<T, N>> for Coordinate<T, N> {
+    type Output = Self;
+    fn add(self, rhs: Coordinate<T, N>) -> Self::Output {
+        self.zip_map(&rhs, |a, b| a + b)
     }
 }
 
-impl<const N: usize> DerefMut for UCoordinate<N> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+impl<T: Scalar, const N: usize> CheckedAdd<Coordinate<T, N>> for Coordinate<T, N> {
+    fn checked_add(&self, v: &Coordinate<T, N>) -> Option<Self::Output> {
+        self.try_zip_map(v, |a, b| a.checked_add(b))
     }
 }
 
-impl<const N: usize> Add<UCoordinate<N>> for UCoordinate<N> {
+impl<T: Scalar, const N: usize> Sub<Coordinate<T, N>> for Coordinate<T, N> {
     type Output = Self;
-    fn add(self, rhs: UCoordinate<N>) -> Self::Output {
-        let mut result = self.0.clone();
-        for (target, to_add) in result.iter_mut().zip_eq(rhs.0.iter().copied()) {
-            *target += to_add;
-        }
-        Self(result)
+    fn sub(self, rhs: Coordinate<T, N>) -> Self::Output {
+        self.zip_map(&rhs, |a, b| a - b)
+    }
+}
+
+impl<T: Scalar, const N: usize> CheckedSub<Coordinate<T, N>> for Coordinate<T, N> {
+    fn checked_sub(&self, v: &Coordinate<T, N>) -> Option<Self::Output> {
+        self.try_zip_map(v, |a, b| a.checked_sub(b))
+    }
+}
+
+impl<T: SignedScalar, const N: usize> Neg for Coordinate<T, N> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(|x| -x))
+    }
+}
+
+impl<T: Scalar, const N: usize> AddAssign<Coordinate<T, N>> for Coordinate<T, N> {
+    fn add_assign(&mut self, rhs: Coordinate<T, N>) {
+        *self = *self + rhs;
     }
 }
 
-impl<const N: usize> CheckedAdd<UCoordinate<N>> for UCoordinate<N> {
-    fn checked_add(&self, v: &UCoordinate<N>) -> Option<Self::Output> {
-        let mut result = self.0.clone();
-        for (target, to_add) in result.iter_mut().zip_eq(v.0.iter().copied()) {
-            *target = target.checked_add(to_add)?
+impl<T: Scalar, const N: usize> SubAssign<Coordinate<T, N>> for Coordinate<T, N> {
+    fn sub_assign(&mut self, rhs: Coordinate<T, N>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Scalar, const N: usize> Mul<T> for Coordinate<T, N> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0.map(|x| x * rhs))
+    }
+}
+
+impl<T: Scalar, const N: usize> CheckedMul<T> for Coordinate<T, N> {
+    fn checked_mul(&self, v: &T) -> Option<Self::Output> {
+        let mut result = self.0;
+        for target in result.iter_mut() {
+            *target = target.checked_mul(*v)?;
         }
         Some(Self(result))
     }
 }
 
-impl<const N: usize> Sub<UCoordinate<N>> for UCoordinate<N> {
+impl<T: Scalar, const N: usize> MulAssign<T> for Coordinate<T, N> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+/// Truncating division of each component, like the underlying integer division
+impl<T: Scalar, const N: usize> Div<T> for Coordinate<T, N> {
     type Output = Self;
-    fn sub(self, rhs: UCoordinate<N>) -> Self::Output {
-        let mut result = self.0.clone();
-        for (target, to_add) in result.iter_mut().zip_eq(rhs.0.iter().copied()) {
-            *target -= to_add;
-        }
-        Self(result)
+    fn div(self, rhs: T) -> Self::Output {
+        Self(self.0.map(|x| x / rhs))
     }
 }
 
-impl<const N: usize> CheckedSub<UCoordinate<N>> for UCoordinate<N> {
-    fn checked_sub(&self, v: &UCoordinate<N>) -> Option<Self::Output> {
-        let mut result = self.0.clone();
-        for (target, to_add) in result.iter_mut().zip_eq(v.0.iter().copied()) {
-            *target = target.checked_sub(to_add)?
+impl<T: Scalar, const N: usize> CheckedDiv<T> for Coordinate<T, N> {
+    fn checked_div(&self, v: &T) -> Option<Self::Output> {
+        let mut result = self.0;
+        for target in result.iter_mut() {
+            *target = target.checked_div(*v)?;
         }
         Some(Self(result))
     }
 }
 
-impl<const N: usize> TryFrom<UCoordinate<N>> for ICoordinate<N> {
-    type Error = UCoordinate<N>;
-    fn try_from(value: UCoordinate<N>) -> Result<Self, Self::Error> {
-        let mut result = [0isize; N];
-        for (idx, u) in value.0.iter().copied().enumerate() {
-            let i: isize = u.try_into().map_err(|_| value)?;
-            result[idx] = i;
-        }
-        Ok(result.into())
+impl<T: Scalar, const N: usize> DivAssign<T> for Coordinate<T, N> {
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
     }
 }
 
-impl<const N: usize> TryFrom<ICoordinate<N>> for UCoordinate<N> {
-    type Error = ICoordinate<N>;
-    fn try_from(value: ICoordinate<N>) -> Result<Self, Self::Error> {
-        let mut result = [0usize; N];
-        for (idx, i) in value.0.iter().copied().enumerate() {
-            let u: usize = i.try_into().map_err(|_| value)?;
-            result[idx] = u;
+// A blanket TryFrom between any two component types would overlap with the reflexive impl, so
+// each pair of distinct types is listed out
+macro_rules! impl_try_from_coordinate {
+    ($from:ty => $($to:ty),*) => {$(
+        impl<const N: usize> TryFrom<Coordinate<$from, N>> for Coordinate<$to, N> {
+            type Error = Coordinate<$from, N>;
+            fn try_from(value: Coordinate<$from, N>) -> Result<Self, Self::Error> {
+                value.try_cast().ok_or(value)
+            }
         }
-        Ok(result.into())
-    }
+    )*};
 }
 
-impl Add<Direction> for UCoordinate<2> {
-    type Output = UCoordinate<2>;
+impl_try_from_coordinate!(i8 => i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+impl_try_from_coordinate!(i16 => i8, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+impl_try_from_coordinate!(i32 => i8, i16, i64, i128, isize, u8, u16, u32, u64, usize);
+impl_try_from_coordinate!(i64 => i8, i16, i32, i128, isize, u8, u16, u32, u64, usize);
+impl_try_from_coordinate!(i128 => i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_try_from_coordinate!(isize => i8, i16, i32, i64, i128, u8, u16, u32, u64, usize);
+impl_try_from_coordinate!(u8 => i8, i16, i32, i64, i128, isize, u16, u32, u64, usize);
+impl_try_from_coordinate!(u16 => i8, i16, i32, i64, i128, isize, u8, u32, u64, usize);
+impl_try_from_coordinate!(u32 => i8, i16, i32, i64, i128, isize, u8, u16, u64, usize);
+impl_try_from_coordinate!(u64 => i8, i16, i32, i64, i128, isize, u8, u16, u32, usize);
+impl_try_from_coordinate!(usize => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64);
+
+impl<T: Scalar> Add<Direction> for Coordinate<T, 2> {
+    type Output = Self;
     fn add(self, rhs: Direction) -> Self::Output {
         let [row, col] = self.0;
         match rhs {
-            Direction::North => Self([row - 1, col]),
-            Direction::East => Self([row, col + 1]),
-            Direction::South => Self([row + 1, col]),
-            Direction::West => Self([row, col - 1]),
+            Direction::North => Self([row - T::ONE, col]),
+            Direction::East => Self([row, col + T::ONE]),
+            Direction::South => Self([row + T::ONE, col]),
+            Direction::West => Self([row, col - T::ONE]),
         }
     }
 }
 
-impl CheckedAdd<Direction> for UCoordinate<2> {
+impl<T: Scalar> CheckedAdd<Direction> for Coordinate<T, 2> {
     fn checked_add(&self, v: &Direction) -> Option<Self::Output> {
         let [row, col] = self.0;
         let v = *v;
         Some(match v {
-            Direction::North => Self([row.checked_sub(1)?, col]),
-            Direction::East => Self([row, col.checked_add(1)?]),
-            Direction::South => Self([row.checked_add(1)?, col]),
-            Direction::West => Self([row, col.checked_sub(1)?]),
+            Direction::North => Self([row.checked_sub(T::ONE)?, col]),
+            Direction::East => Self([row, col.checked_add(T::ONE)?]),
+            Direction::South => Self([row.checked_add(T::ONE)?, col]),
+            Direction::West => Self([row, col.checked_sub(T::ONE)?]),
         })
     }
 }
 
-impl Sub<Direction> for UCoordinate<2> {
-    type Output = UCoordinate<2>;
+impl<T: Scalar> Sub<Direction> for Coordinate<T, 2> {
+    type Output = Self;
     fn sub(self, rhs: Direction) -> Self::Output {
         let [row, col] = self.0;
         match rhs {
-            Direction::North => Self([row + 1, col]),
-            Direction::East => Self([row, col - 1]),
-            Direction::South => Self([row - 1, col]),
-            Direction::West => Self([row, col + 1]),
+            Direction::North => Self([row + T::ONE, col]),
+            Direction::East => Self([row, col - T::ONE]),
+            Direction::South => Self([row - T::ONE, col]),
+            Direction::West => Self([row, col + T::ONE]),
         }
     }
 }
 
-impl CheckedSub<Direction> for UCoordinate<2> {
+impl<T: Scalar> CheckedSub<Direction> for Coordinate<T, 2> {
     fn checked_sub(&self, v: &Direction) -> Option<Self::Output> {
-        let [row, col] = self.0;
-        let v = *v;
-        Some(match v {
-            Direction::North => Self([row.checked_add(1)?, col]),
-            Direction::East => Self([row, col.checked_sub(1)?]),
-            Direction::South => Self([row.checked_sub(1)?, col]),
-            Direction::West => Self([row, col.checked_add(1)?]),
-        })
+        self.checked_add(&v.opposite())
+    }
+}
+
+impl<T: Scalar> AddAssign<Direction> for Coordinate<T, 2> {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self = *self + rhs;
     }
 }
 
-pub fn twice_shoelace(it: impl Iterator<Item=ICoordinate<2>> + Clone + ExactSizeIterator) -> usize {
-    // TODO: without using circular windows I suspect I can relax these bounds - have a single
-    // iteration cover shoelace, boundary, and pick?
+impl<T: Scalar> SubAssign<Direction> for Coordinate<T, 2> {
+    fn sub_assign(&mut self, rhs: Direction) {
+        *self = *self - rhs;
+    }
+}
+
+/// Uniform access to the components of either coordinate type, widened so that mixed signed and
+/// unsigned arithmetic cannot overflow
+pub trait LatticePoint: Copy + Eq {
+    const DIMENSIONS: usize;
+
+    fn component(&self, axis: usize) -> i128;
+
+    /// None if there are the wrong number of components or any is out of range
+    fn try_from_components(components: impl IntoIterator<Item=i128>) -> Option<Self>;
+}
+
+impl<T: Scalar, const N: usize> LatticePoint for Coordinate<T, N> {
+    const DIMENSIONS: usize = N;
+
+    fn component(&self, axis: usize) -> i128 {
+        self.0[axis].to_i128()
+    }
+
+    fn try_from_components(components: impl IntoIterator<Item=i128>) -> Option<Self> {
+        let mut result = [T::ZERO; N];
+        let mut components = components.into_iter();
+        for target in result.iter_mut() {
+            *target = T::try_from_i128(components.next()?)?;
+        }
+        components.next().is_none().then_some(Self(result))
+    }
+}
+
+/// Summed in `isize`; `twice_shoelace_in` takes a wider accumulator
+pub fn twice_shoelace<T: Scalar>(it: impl ExactSizeIterator<Item=Coordinate<T, 2>> + Clone) -> usize {
+    twice_shoelace_in::<isize, T>(it) as usize
+}
+
+pub fn twice_shoelace_in<A: SignedScalar, T: Scalar>(
+    it: impl ExactSizeIterator<Item=Coordinate<T, 2>> + Clone,
+) -> A {
+    let twice_area = twice_signed_shoelace_in::<A, T>(it);
+    if twice_area < A::ZERO {
+        A::ZERO.checked_sub(twice_area).expect("shoelace overflows the accumulator")
+    } else {
+        twice_area
+    }
+}
+
+/// Summed in `i128`
+pub fn twice_signed_shoelace<T: Scalar>(
+    it: impl ExactSizeIterator<Item=Coordinate<T, 2>> + Clone,
+) -> i128 {
+    twice_signed_shoelace_in::<i128, T>(it)
+}
+
+/// Negative when the vertices go clockwise as drawn with rows increasing downward. Products are
+/// summed in `A`, chosen by the caller, and panic if they overflow it. See also
+/// `polygon::Polygon` for boundary and Pick's theorem.
+pub fn twice_signed_shoelace_in<A: SignedScalar, T: Scalar>(
+    it: impl ExactSizeIterator<Item=Coordinate<T, 2>> + Clone,
+) -> A {
+    let overflow = "shoelace overflows the accumulator";
+    let widen = |x: T| A::try_from_i128(x.to_i128()).expect(overflow);
+    // TODO: without using circular windows I suspect I can relax these bounds
     it.circular_tuple_windows::<(_, _)>()
         .map(|(a, b)| {
-            let a: (_, _) = a.into();
-            let b: (_, _) = b.into();
-            a.0 * b.1 - b.0 * a.1
+            let [a0, a1] = a.0.map(widen);
+            let [b0, b1] = b.0.map(widen);
+            a0.checked_mul(b1).zip(b0.checked_mul(a1))
+                .and_then(|(x, y)| x.checked_sub(y))
+                .expect(overflow)
         })
-        .sum::<isize>()
-        .abs() as usize
+        .fold(A::ZERO, |acc, term| acc.checked_add(term).expect(overflow))
 }
 
-// TODO: is_adjacent, shoelace/pick, etc
+// TODO: is_adjacent, etc
 
 // More conversions
 
 // 2D
-impl From<ICoordinate<2>> for (isize, isize) {
-    fn from(value: ICoordinate<2>) -> Self {
-        (value.as_ref()[0], value.as_ref()[1])
+impl<T: Scalar> From<Coordinate<T, 2>> for (T, T) {
+    fn from(value: Coordinate<T, 2>) -> Self {
+        (value.0[0], value.0[1])
     }
 }
 
-impl From<(isize, isize)> for ICoordinate<2> {
-    fn from(value: (isize, isize)) -> Self {
+impl<T: Scalar> From<(T, T)> for Coordinate<T, 2> {
+    fn from(value: (T, T)) -> Self {
         [value.0, value.1].into()
     }
 }
 
-impl From<UCoordinate<2>> for (usize, usize) {
-    fn from(value: UCoordinate<2>) -> Self {
-        (value.as_ref()[0], value.as_ref()[1])
-    }
-}
-
-impl From<(usize, usize)> for UCoordinate<2> {
-    fn from(value: (usize, usize)) -> Self {
-        [value.0, value.1].into()
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_direction_notation() {
+        assert_eq!(Direction::try_from('v'), Ok(Direction::South));
+        assert_eq!("L".parse(), Ok(Direction::West));
+        assert_eq!(Direction::parse_with('0', DirectionNotation::AocHex), Ok(Direction::East));
+        assert!(Direction::parse_with('N', DirectionNotation::Urdl).is_err());
+        assert!("NE".parse::<Direction>().is_err());
+        assert_eq!(Direction::North.display(DirectionNotation::Arrow).to_string(), "^");
+        assert_eq!(Direction::West.to_string(), "W");
+        for d in Direction::ALL {
+            for turn in Turn::ALL {
+                assert_eq!(d.turn_to(d.turn(turn)), turn);
+            }
+        }
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+    }
+
+    #[test]
+    fn test_icoordinate_arithmetic() {
+        let a = ICoordinate::new([3, -4]);
+        let b = ICoordinate::new([-1, 2]);
+        assert_eq!(a - b, ICoordinate::new([4, -6]));
+        assert_eq!(-a, ICoordinate::new([-3, 4]));
+        assert_eq!(a * 3, ICoordinate::new([9, -12]));
+        assert_eq!(a / 2, ICoordinate::new([1, -2]));
+        assert_eq!(a.dot(&b), -11);
+        assert_eq!(a.signum(), ICoordinate::new([1, -1]));
+        assert_eq!(a.component_min(&b), ICoordinate::new([-1, -4]));
+        assert_eq!(a + Direction::North, ICoordinate::new([2, -4]));
+        assert_eq!(ICoordinate::new([isize::MAX, 0]).checked_mul(&2), None);
+        let mut c = a;
+        c -= b;
+        c *= 2;
+        assert_eq!(c, ICoordinate::new([8, -12]));
+    }
+
+    #[test]
+    fn test_ucoordinate_arithmetic() {
+        let a = UCoordinate::new([3, 4]);
+        assert_eq!(a * 2, UCoordinate::new([6, 8]));
+        assert_eq!(a.checked_div(&0), None);
+        assert_eq!(a.component_max(&UCoordinate::new([5, 1])), UCoordinate::new([5, 4]));
+        assert_eq!(a.dot(&UCoordinate::new([5, 1])), 19);
+    }
+
+    #[test]
+    fn test_other_component_types() {
+        let small: Coordinate<u8, 2> = Coordinate::new([200, 3]);
+        assert_eq!(small.checked_add(&Coordinate::new([100, 0])), None);
+        assert_eq!(small.cast::<u32>() * 2, Coordinate::new([400, 6]));
+        assert_eq!(small.try_cast::<i8>(), None);
+        assert_eq!(small.try_cast::<i32>(), Some(Coordinate::new([200, 3])));
+        assert_eq!(Coordinate::<i32, 2>::from(Direction::West), Coordinate::new([0, -1]));
+
+        assert_eq!(UCoordinate::<2>::try_from(Coordinate::<i32, 2>::new([1, -1])), Err(Coordinate::new([1, -1])));
+        assert_eq!(Coordinate::<u16, 2>::try_from(small), Ok(Coordinate::new([200, 3])));
+        assert_eq!(Coordinate::<i8, 2>::try_from(small), Err(small));
+
+        // The products overflow i32 but can be summed in something wider
+        let big = i32::MAX;
+        let square = [[0, 0], [big, 0], [big, big], [0, big]].map(Coordinate::<i32, 2>::new);
+        assert_eq!(twice_shoelace_in::<i128, _>(square.into_iter()), 2 * (big as i128).pow(2));
+        assert_eq!(twice_shoelace(square.map(|c| c / big).into_iter()), 2);
+    }
+
+    #[test]
+    fn test_distances_at_the_extremes() {
+        let [low, high] = [i128::MIN, i128::MAX].map(|x| Coordinate::<i128, 2>::new([x, 0]));
+        assert_eq!(low.checked_taxicab_dist(&high), None);
+        assert_eq!(low.checked_king_dist(&high), None);
+        let wide = Coordinate::<i128, 2>::new([usize::MAX as i128, usize::MAX as i128]);
+        let origin = Coordinate::<i128, 2>::origin();
+        assert_eq!(origin.checked_king_dist(&wide), Some(usize::MAX));
+        assert_eq!(origin.checked_taxicab_dist(&wide), None);
+        assert_eq!(origin.checked_taxicab_dist(&(wide / 2)), Some(usize::MAX - 1));
+        let corners = [isize::MIN, isize::MAX].map(|x| ICoordinate::<2>::new([x, x]));
+        assert_eq!(corners[0].king_dist(&corners[1]), usize::MAX);
     }
 }
@@ -0,0 +1,183 @@
+use std::iter;
+use itertools::Itertools;
+use super::LatticePoint;
+
+/// Axis-aligned box between two corners, inclusive on both ends
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+pub struct AaBox<C> {
+    start: C,
+    end: C,
+}
+
+impl<C: LatticePoint> AaBox<C> {
+    /// Any two opposite corners, in any order
+    pub fn new(a: C, b: C) -> Self {
+        let start = C::try_from_components((0..C::DIMENSIONS)
+            .map(|axis| a.component(axis).min(b.component(axis))))
+            .expect("minimum of valid components is valid");
+        let end = C::try_from_components((0..C::DIMENSIONS)
+            .map(|axis| a.component(axis).max(b.component(axis))))
+            .expect("maximum of valid components is valid");
+        Self { start, end }
+    }
+
+    pub fn point(c: C) -> Self {
+        Self { start: c, end: c }
+    }
+
+    /// Corner with the smallest value on every axis
+    pub fn start(&self) -> C {
+        self.start
+    }
+
+    /// Corner with the largest value on every axis
+    pub fn end(&self) -> C {
+        self.end
+    }
+
+    /// Number of lattice points along `axis`
+    pub fn len_along(&self, axis: usize) -> u128 {
+        (self.end.component(axis) - self.start.component(axis)) as u128 + 1
+    }
+
+    pub fn volume(&self) -> u128 {
+        (0..C::DIMENSIONS).map(|axis| self.len_along(axis)).product()
+    }
+
+    pub fn contains(&self, c: &C) -> bool {
+        (0..C::DIMENSIONS).all(|axis| {
+            (self.start.component(axis)..=self.end.component(axis)).contains(&c.component(axis))
+        })
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.start) && self.contains(&other.end)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let start = (0..C::DIMENSIONS)
+            .map(|axis| self.start.component(axis).max(other.start.component(axis)))
+            .collect_vec();
+        let end = (0..C::DIMENSIONS)
+            .map(|axis| self.end.component(axis).min(other.end.component(axis)))
+            .collect_vec();
+        if start.iter().zip_eq(end.iter()).any(|(s, e)| s > e) {
+            return None;
+        }
+        Some(Self {
+            start: C::try_from_components(start)?,
+            end: C::try_from_components(end)?,
+        })
+    }
+
+    /// Smallest box containing both
+    pub fn bounding_union(&self, other: &Self) -> Self {
+        Self::new(
+            Self::new(self.start, other.start).start,
+            Self::new(self.end, other.end).end,
+        )
+    }
+
+    /// Disjoint boxes covering every point of `self` not in `other`
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut result = vec![];
+        // Peel off slabs below and above the overlap one axis at a time, shrinking the remainder
+        let mut start = (0..C::DIMENSIONS).map(|axis| self.start.component(axis)).collect_vec();
+        let mut end = (0..C::DIMENSIONS).map(|axis| self.end.component(axis)).collect_vec();
+        for axis in 0..C::DIMENSIONS {
+            let (low, high) = (overlap.start.component(axis), overlap.end.component(axis));
+            if start[axis] < low {
+                let mut slab_end = end.clone();
+                slab_end[axis] = low - 1;
+                result.push(Self::from_wide(&start, &slab_end));
+                start[axis] = low;
+            }
+            if end[axis] > high {
+                let mut slab_start = start.clone();
+                slab_start[axis] = high + 1;
+                result.push(Self::from_wide(&slab_start, &end));
+                end[axis] = high;
+            }
+        }
+        result
+    }
+
+    fn from_wide(start: &[i128], end: &[i128]) -> Self {
+        Self {
+            start: C::try_from_components(start.iter().copied()).expect("within original box"),
+            end: C::try_from_components(end.iter().copied()).expect("within original box"),
+        }
+    }
+
+    /// None if either corner would leave the coordinate type's range
+    pub fn translated_by<D: LatticePoint>(&self, offset: &D) -> Option<Self> {
+        assert_eq!(C::DIMENSIONS, D::DIMENSIONS, "offset has the wrong number of dimensions");
+        let shift = |c: &C| C::try_from_components((0..C::DIMENSIONS)
+            .map(|axis| c.component(axis) + offset.component(axis)));
+        Some(Self { start: shift(&self.start)?, end: shift(&self.end)? })
+    }
+
+    pub fn translated_along(&self, axis: usize, delta: i128) -> Option<Self> {
+        let shift = |c: &C| C::try_from_components((0..C::DIMENSIONS)
+            .map(|a| c.component(a) + if a == axis { delta } else { 0 }));
+        Some(Self { start: shift(&self.start)?, end: shift(&self.end)? })
+    }
+
+    /// Every contained point, with the last axis varying fastest
+    pub fn iter(&self) -> impl Iterator<Item=C> {
+        let Self { start, end } = *self;
+        let mut next = Some((0..C::DIMENSIONS).map(|axis| start.component(axis)).collect_vec());
+        iter::from_fn(move || {
+            let current = next.take()?;
+            let mut advanced = current.clone();
+            for axis in (0..C::DIMENSIONS).rev() {
+                if advanced[axis] < end.component(axis) {
+                    advanced[axis] += 1;
+                    next = Some(advanced);
+                    break;
+                }
+                advanced[axis] = start.component(axis);
+            }
+            C::try_from_components(current)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::coordinate::{ICoordinate, UCoordinate};
+
+    #[test]
+    fn test_subtract() {
+        let outer = AaBox::new(ICoordinate::new([0, 0, 0]), ICoordinate::new([3, 3, 3]));
+        let inner = AaBox::new(ICoordinate::new([1, 1, 1]), ICoordinate::new([2, 2, 5]));
+        let pieces = outer.subtract(&inner);
+        assert_eq!(pieces.iter().map(|b| b.volume()).sum::<u128>(), 64 - 12);
+        for (a, b) in pieces.iter().tuple_combinations() {
+            assert!(!a.intersects(b));
+        }
+        assert!(pieces.iter().all(|p| !p.intersects(&inner) && outer.contains_box(p)));
+    }
+
+    #[test]
+    fn test_iter_and_translate() {
+        let block = AaBox::new(UCoordinate::new([2, 0, 5]), UCoordinate::new([0, 0, 5]));
+        assert_eq!(block.volume(), 3);
+        assert_eq!(block.iter().collect_vec(), [
+            UCoordinate::new([0, 0, 5]), UCoordinate::new([1, 0, 5]), UCoordinate::new([2, 0, 5]),
+        ]);
+        assert_eq!(block.translated_along(2, -6), None);
+        assert_eq!(
+            block.translated_along(2, -4).map(|b| b.start()),
+            Some(UCoordinate::new([0, 0, 1])),
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt::{Display, Formatter, Write};
+use std::collections::VecDeque;
+use super::Direction;
+use super::grid::{Grid, Point};
+
+/// A set of directions stored as a 4-bit mask
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub struct DirectionSet(u8);
+
+impl DirectionSet {
+    pub const EMPTY: Self = Self(0);
+    pub const ALL: Self = Self(0b1111);
+
+    const fn bit(direction: Direction) -> u8 {
+        1 << direction as u8
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub const fn contains(self, direction: Direction) -> bool {
+        self.0 & Self::bit(direction) != 0
+    }
+
+    /// Returns true if the direction was not already present, like `HashSet::insert`
+    pub fn insert(&mut self, direction: Direction) -> bool {
+        let was_present = self.contains(direction);
+        self.0 |= Self::bit(direction);
+        !was_present
+    }
+
+    pub fn remove(&mut self, direction: Direction) -> bool {
+        let was_present = self.contains(direction);
+        self.0 &= !Self::bit(direction);
+        was_present
+    }
+
+    pub fn iter(self) -> impl Iterator<Item=Direction> {
+        Direction::ALL.into_iter().filter(move |d| self.contains(*d))
+    }
+}
+
+impl FromIterator<Direction> for DirectionSet {
+    fn from_iter<I: IntoIterator<Item=Direction>>(iter: I) -> Self {
+        let mut result = Self::EMPTY;
+        for direction in iter {
+            result.insert(direction);
+        }
+        result
+    }
+}
+
+impl Display for DirectionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char(if self.is_empty() { '.' } else { '#' })
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct BeamTrace {
+    directions: Grid<DirectionSet>,
+    has_cycle: bool,
+}
+
+impl BeamTrace {
+    /// For each cell, the directions beams were travelling in when they entered it
+    pub fn directions(&self) -> &Grid<DirectionSet> {
+        &self.directions
+    }
+
+    pub fn is_energized(&self, point: &Point) -> bool {
+        !self.directions[*point].is_empty()
+    }
+
+    pub fn energized(&self) -> impl Iterator<Item=Point> + '_ {
+        self.directions.iter_idxs()
+            .filter(|idx| self.is_energized(idx))
+    }
+
+    pub fn energized_count(&self) -> usize {
+        self.directions.iter_rows()
+            .flat_map(|row| row.iter())
+            .filter(|d| !d.is_empty())
+            .count()
+    }
+
+    /// Whether some beam returns to a cell it already passed through in the same direction. Merging
+    /// beams from different splitters do not count.
+    pub fn has_cycle(&self) -> bool {
+        self.has_cycle
+    }
+}
+
+/// Follow a beam entering `start` travelling in `direction`. `redirect` is given the contents of a
+/// cell and the direction the beam was travelling when it entered, and returns the directions in
+/// which beams leave the cell. Beams leaving the grid are dropped.
+pub fn trace_beam<T, R: IntoIterator<Item=Direction>>(
+    grid: &Grid<T>,
+    start: Point,
+    direction: Direction,
+    mut redirect: impl FnMut(&T, Direction) -> R,
+) -> BeamTrace {
+    assert!(grid.is_in_bounds(&start));
+    let (rows, cols) = grid.shape();
+    let mut directions = Grid::full(rows, cols, DirectionSet::EMPTY);
+    // Outgoing directions per (cell, incoming direction), used to look for cycles afterward
+    let mut outgoing = Grid::full(rows, cols, [DirectionSet::EMPTY; 4]);
+
+    let mut operations = VecDeque::new();
+    operations.push_back((start, direction));
+    while let Some((point, incoming)) = operations.pop_front() {
+        if !directions[point].insert(incoming) {
+            continue;
+        }
+        let out: DirectionSet = redirect(&grid[point], incoming).into_iter().collect();
+        outgoing[point][incoming as usize] = out;
+        for dir in out.iter() {
+            if let Some(next) = grid.checked_add(&point, &dir) {
+                operations.push_back((next, dir));
+            }
+        }
+    }
+
+    let has_cycle = has_cycle(grid, &directions, &outgoing);
+    BeamTrace { directions, has_cycle }
+}
+
+/// Kahn's algorithm over the visited (cell, direction) states
+fn has_cycle<T>(
+    grid: &Grid<T>,
+    directions: &Grid<DirectionSet>,
+    outgoing: &Grid<[DirectionSet; 4]>,
+) -> bool {
+    let successors = |(point, incoming): (Point, Direction)| {
+        outgoing[point][incoming as usize].iter()
+            .filter_map(move |dir| grid.checked_add(&point, &dir).map(|next| (next, dir)))
+    };
+    let states = || directions.iter_idxs()
+        .flat_map(|idx| directions[idx].iter().map(move |d| (idx, d)));
+
+    let mut in_degree = Grid::full(grid.rows(), grid.cols(), [0usize; 4]);
+    for state in states() {
+        for (next, dir) in successors(state) {
+            in_degree[next][dir as usize] += 1;
+        }
+    }
+    let mut ready: Vec<_> = states()
+        .filter(|(idx, d)| in_degree[*idx][*d as usize] == 0)
+        .collect();
+    let mut removed = 0usize;
+    while let Some(state) = ready.pop() {
+        removed += 1;
+        for (next, dir) in successors(state) {
+            let count = &mut in_degree[next][dir as usize];
+            *count -= 1;
+            if *count == 0 {
+                ready.push((next, dir));
+            }
+        }
+    }
+    removed != states().count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mirrors(c: &u8, direction: Direction) -> Vec<Direction> {
+        match (*c as char, direction) {
+            ('/', _) => vec![direction.transpose_secondary()],
+            ('\\', _) => vec![direction.transpose()],
+            ('-', Direction::North | Direction::South) => vec![Direction::East, Direction::West],
+            ('|', Direction::East | Direction::West) => vec![Direction::North, Direction::South],
+            _ => vec![direction],
+        }
+    }
+
+    fn grid(s: &str) -> Grid<u8> {
+        s.lines()
+            .map(|line| line.as_bytes().to_vec())
+            .collect::<Vec<_>>().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_trace_example() {
+        let grid = grid(r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....");
+        let trace = trace_beam(&grid, Point::origin(), Direction::East, mirrors);
+        assert_eq!(trace.energized_count(), 46);
+    }
+
+    #[test]
+    fn test_trace_cycle() {
+        let grid = grid(r"/.\
+\./");
+        let trace = trace_beam(&grid, (0, 1).into(), Direction::East, mirrors);
+        assert_eq!(trace.energized_count(), 6);
+        assert!(trace.has_cycle());
+    }
+
+    #[test]
+    fn test_trace_no_cycle() {
+        let grid = grid(r"..\
+.-/");
+        let trace = trace_beam(&grid, Point::origin(), Direction::East, mirrors);
+        assert_eq!(trace.energized_count(), 6);
+        assert_eq!(
+            trace.directions()[Point::new([1, 1])].iter().collect::<Vec<_>>(),
+            [Direction::West],
+        );
+        assert!(!trace.has_cycle());
+    }
+}
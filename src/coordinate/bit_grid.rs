@@ -0,0 +1,340 @@
+use std::fmt::{Display, Formatter, Write};
+use std::ops::{BitAndAssign, BitOrAssign, SubAssign};
+use itertools::Itertools;
+use crate::util::CheckedAdd;
+use super::Direction;
+use super::grid::{Grid, Point};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A grid of booleans stored one bit per cell, with the same shape semantics as `Grid`
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct BitGrid {
+    data: Vec<u64>,
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self::full(rows, cols, false)
+    }
+
+    pub fn full(rows: usize, cols: usize, value: bool) -> Self {
+        assert!(rows > 0);
+        assert!(cols > 0);
+        let words_per_row = cols.div_ceil(WORD_BITS);
+        let fill = if value { u64::MAX } else { 0 };
+        let mut result = Self { data: vec![fill; rows * words_per_row], rows, cols, words_per_row };
+        result.clear_padding();
+        result
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(Point) -> bool) -> Self {
+        let mut result = Self::new(rows, cols);
+        for idx in result.iter_idxs() {
+            if f(idx) {
+                result.insert(idx);
+            }
+        }
+        result
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, mut f: impl FnMut(&T) -> bool) -> Self {
+        Self::from_fn(grid.rows(), grid.cols(), |idx| f(&grid[idx]))
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_in_bounds(&self, coordinate: &Point) -> bool {
+        (0..self.rows).contains(&coordinate[0]) && (0..self.cols).contains(&coordinate[1])
+    }
+
+    fn locate(&self, coordinate: &Point) -> (usize, u64) {
+        assert!(self.is_in_bounds(coordinate), "{coordinate:?} out of bounds");
+        let [row, col] = **coordinate;
+        (row * self.words_per_row + col / WORD_BITS, 1 << (col % WORD_BITS))
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<bool> {
+        let coordinate = Point::new([row, col]);
+        if self.is_in_bounds(&coordinate) {
+            Some(self.contains(&coordinate))
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, coordinate: &Point) -> bool {
+        let (word, mask) = self.locate(coordinate);
+        self.data[word] & mask != 0
+    }
+
+    /// Returns true if the cell was not already set, like `HashSet::insert`
+    pub fn insert(&mut self, coordinate: Point) -> bool {
+        let (word, mask) = self.locate(&coordinate);
+        let was_set = self.data[word] & mask != 0;
+        self.data[word] |= mask;
+        !was_set
+    }
+
+    /// Returns true if the cell was set
+    pub fn remove(&mut self, coordinate: &Point) -> bool {
+        let (word, mask) = self.locate(coordinate);
+        let was_set = self.data[word] & mask != 0;
+        self.data[word] &= !mask;
+        was_set
+    }
+
+    pub fn set(&mut self, coordinate: Point, value: bool) {
+        if value {
+            self.insert(coordinate);
+        } else {
+            self.remove(&coordinate);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn count_zeros(&self) -> usize {
+        self.rows * self.cols - self.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.iter().all(|w| *w == 0)
+    }
+
+    fn assert_same_shape(&self, other: &Self) {
+        assert_eq!(self.shape(), other.shape(), "shape mismatch");
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.assert_same_shape(other);
+        for (a, b) in self.data.iter_mut().zip_eq(other.data.iter()) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.assert_same_shape(other);
+        for (a, b) in self.data.iter_mut().zip_eq(other.data.iter()) {
+            *a &= b;
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.assert_same_shape(other);
+        for (a, b) in self.data.iter_mut().zip_eq(other.data.iter()) {
+            *a &= !b;
+        }
+    }
+
+    pub fn invert(&mut self) {
+        for w in self.data.iter_mut() {
+            *w = !*w;
+        }
+        self.clear_padding();
+    }
+
+    /// Move every set cell `distance` steps in `direction`; cells moved off the grid are dropped
+    pub fn shift(&mut self, direction: Direction, distance: usize) {
+        let wpr = self.words_per_row;
+        match direction {
+            Direction::North | Direction::South => {
+                let distance = distance.min(self.rows);
+                let moved = distance * wpr;
+                if direction == Direction::North {
+                    self.data.copy_within(moved.., 0);
+                    let len = self.data.len();
+                    self.data[len - moved..].fill(0);
+                } else {
+                    let len = self.data.len();
+                    self.data.copy_within(..len - moved, moved);
+                    self.data[..moved].fill(0);
+                }
+            },
+            Direction::East | Direction::West => {
+                let word_shift = (distance / WORD_BITS).min(wpr);
+                let bit_shift = distance % WORD_BITS;
+                for row in self.data.chunks_mut(wpr) {
+                    if direction == Direction::East {
+                        shift_words_up(row, word_shift, bit_shift);
+                    } else {
+                        shift_words_down(row, word_shift, bit_shift);
+                    }
+                }
+                self.clear_padding();
+            },
+        }
+    }
+
+    pub fn shifted(&self, direction: Direction, distance: usize) -> Self {
+        let mut result = self.clone();
+        result.shift(direction, distance);
+        result
+    }
+
+    /// Every cell that is set or orthogonally adjacent to a set cell - one step of a BFS frontier
+    pub fn spread(&self) -> Self {
+        let mut result = self.clone();
+        for direction in Direction::ALL {
+            result.union_with(&self.shifted(direction, 1));
+        }
+        result
+    }
+
+    pub fn iter_idxs(&self) -> impl Iterator<Item=Point> {
+        (0..self.rows).cartesian_product(0..self.cols)
+            .map(|(r, c)| (r, c).into())
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item=Point> + '_ {
+        self.iter_idxs().filter(|idx| self.contains(idx))
+    }
+
+    pub fn neighbors(&self, coordinate: &Point) -> impl Iterator<Item=(Direction, Point)> + '_ {
+        let coordinate = *coordinate;
+        Direction::ALL.iter().copied()
+            .filter_map(move |d| {
+                coordinate.checked_add(&d)
+                    .filter(|n| self.is_in_bounds(n))
+                    .map(|n| (d, n))
+            })
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut result = Grid::full(self.rows, self.cols, false);
+        for idx in self.iter_ones() {
+            result[idx] = true;
+        }
+        result
+    }
+
+    fn clear_padding(&mut self) {
+        let used = self.cols % WORD_BITS;
+        if used == 0 {
+            return;
+        }
+        let mask = (1u64 << used) - 1;
+        for row in self.data.chunks_mut(self.words_per_row) {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+}
+
+/// Shift a little-endian multi-word bit string towards higher bit positions
+fn shift_words_up(words: &mut [u64], word_shift: usize, bit_shift: usize) {
+    let len = words.len();
+    for idx in (0..len).rev() {
+        let src = idx.checked_sub(word_shift);
+        let high = src.map(|s| words[s]).unwrap_or(0);
+        let low = src.and_then(|s| s.checked_sub(1)).map(|s| words[s]).unwrap_or(0);
+        words[idx] = if bit_shift == 0 {
+            high
+        } else {
+            (high << bit_shift) | (low >> (WORD_BITS - bit_shift))
+        };
+    }
+}
+
+/// Shift a little-endian multi-word bit string towards lower bit positions
+fn shift_words_down(words: &mut [u64], word_shift: usize, bit_shift: usize) {
+    let len = words.len();
+    for idx in 0..len {
+        let src = idx + word_shift;
+        let low = words.get(src).copied().unwrap_or(0);
+        let high = words.get(src + 1).copied().unwrap_or(0);
+        words[idx] = if bit_shift == 0 {
+            low
+        } else {
+            (low >> bit_shift) | (high << (WORD_BITS - bit_shift))
+        };
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        self.union_with(rhs)
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        self.intersect_with(rhs)
+    }
+}
+
+impl SubAssign<&BitGrid> for BitGrid {
+    fn sub_assign(&mut self, rhs: &BitGrid) {
+        self.difference_with(rhs)
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(value: &Grid<bool>) -> Self {
+        Self::from_grid(value, |b| *b)
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                f.write_char(if self.contains(&(row, col).into()) { '#' } else { '.' })?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shift_across_words() {
+        let mut grid = BitGrid::new(3, 130);
+        grid.insert((1, 63).into());
+        grid.insert((1, 129).into());
+        grid.shift(Direction::East, 1);
+        assert_eq!(grid.iter_ones().collect_vec(), [Point::new([1, 64])]);
+        grid.shift(Direction::West, 64);
+        assert_eq!(grid.iter_ones().collect_vec(), [Point::new([1, 0])]);
+        grid.shift(Direction::South, 1);
+        assert_eq!(grid.iter_ones().collect_vec(), [Point::new([2, 0])]);
+        grid.shift(Direction::North, 3);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_spread_and_set_ops() {
+        let mut grid = BitGrid::new(3, 3);
+        grid.insert((1, 1).into());
+        let mut spread = grid.spread();
+        assert_eq!(spread.to_string(), ".#.\n###\n.#.\n");
+        spread -= &grid;
+        assert_eq!(spread.count_ones(), 4);
+        spread.invert();
+        assert_eq!(spread.count_ones(), 5);
+        spread &= &grid;
+        assert_eq!(spread, grid);
+    }
+}
@@ -3,10 +3,11 @@ use std::fmt::{Display, Formatter, Write};
 use std::{io, iter};
 use std::io::{BufRead, BufReader};
 use std::ops::{Index, IndexMut};
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use crate::util::{CheckedAdd, CheckedSub};
 use thiserror;
-use super::{Direction, UCoordinate};
+use super::{Direction, ICoordinate, UCoordinate};
+use super::shapes::Metric;
 
 pub type Point = UCoordinate<2>;
 
@@ -167,6 +168,52 @@ impl<T> Grid<T> {
         rotated
     }
 
+    /// In-bounds points exactly `distance` from `center` under `metric`. Only rows and columns
+    /// within the grid are visited, however large `distance` is.
+    pub fn points_at(&self, center: &Point, metric: Metric, distance: usize) -> impl Iterator<Item=Point> + '_ {
+        let [row, col] = center.0;
+        clamped_range(row, distance, self.rows()).flat_map(move |r| {
+            let row_dist = r.abs_diff(row);
+            let cols = match metric {
+                Metric::King if row_dist == distance => Either::Left(clamped_range(col, distance, self.cols())),
+                Metric::King => Either::Right(self.cols_either_side(col, distance)),
+                Metric::Taxicab => Either::Right(self.cols_either_side(col, distance - row_dist)),
+            };
+            cols.map(move |c| (r, c).into())
+        })
+    }
+
+    /// In-bounds points at most `distance` from `center` under `metric`. Only rows and columns
+    /// within the grid are visited, however large `distance` is.
+    pub fn points_within(&self, center: &Point, metric: Metric, distance: usize) -> impl Iterator<Item=Point> + '_ {
+        let [row, col] = center.0;
+        clamped_range(row, distance, self.rows()).flat_map(move |r| {
+            let reach = match metric {
+                Metric::King => distance,
+                Metric::Taxicab => distance - r.abs_diff(row),
+            };
+            clamped_range(col, reach, self.cols()).map(move |c| (r, c).into())
+        })
+    }
+
+    /// The in-bounds columns exactly `offset` either side of `col`
+    fn cols_either_side(&self, col: usize, offset: usize) -> impl Iterator<Item=usize> {
+        let cols = self.cols();
+        [col.checked_sub(offset), col.checked_add(offset)].into_iter()
+            .dedup()
+            .flatten()
+            .filter(move |c| *c < cols)
+    }
+
+    /// The in-bounds points of a king-step walk from `start` to `end`; see `ICoordinate::line_to`
+    pub fn line(&self, start: &Point, end: &Point) -> impl Iterator<Item=Point> + '_ {
+        let start = ICoordinate::try_from(*start).expect("coordinate too large");
+        let end = ICoordinate::try_from(*end).expect("coordinate too large");
+        start.line_to(&end)
+            .filter_map(|p| Point::try_from(p).ok())
+            .filter(|p| self.is_in_bounds(p))
+    }
+
     pub fn iter_idxs(&self) -> impl Iterator<Item=UCoordinate<2>> + '_ {
         (0..self.rows()).cartesian_product(0..self.cols())
             .map(|(r, c)| (r, c).into())
@@ -271,6 +318,11 @@ pub fn get_byte_grid_from_stdin() -> Result<Grid<u8>, Box<dyn Error>> {
     Ok(grid)
 }
 
+/// Indices within `reach` of `middle`, cut off at `len`
+fn clamped_range(middle: usize, reach: usize, len: usize) -> std::ops::Range<usize> {
+    middle.saturating_sub(reach)..middle.saturating_add(reach).saturating_add(1).min(len)
+}
+
 #[cfg(test)]
 mod test_transforms {
     use super::*;
@@ -301,3 +353,34 @@ mod test_transforms {
     }
 }
 
+#[cfg(test)]
+mod test_neighborhoods {
+    use super::*;
+
+    #[test]
+    fn test_points_within() {
+        let grid = Grid::full(3, 4, 0u8);
+        assert_eq!(grid.points_within(&(0, 0).into(), Metric::Taxicab, 2).count(), 6);
+        assert_eq!(grid.points_within(&(1, 1).into(), Metric::King, usize::MAX).count(), 12);
+        assert_eq!(grid.points_within(&(1, 2).into(), Metric::Taxicab, usize::MAX).count(), 12);
+    }
+
+    #[test]
+    fn test_points_at() {
+        let grid = Grid::full(3, 4, 0u8);
+        assert_eq!(grid.points_at(&(1, 1).into(), Metric::King, 1).count(), 8);
+        assert_eq!(grid.points_at(&(0, 0).into(), Metric::King, 2).count(), 5);
+        assert_eq!(grid.points_at(&(0, 0).into(), Metric::Taxicab, 0).collect_vec(), [(0, 0).into()]);
+        assert_eq!(grid.points_at(&(1, 1).into(), Metric::Taxicab, 2).collect_vec(),
+                   [(0, 0).into(), (0, 2).into(), (1, 3).into(), (2, 0).into(), (2, 2).into()]);
+        assert_eq!(grid.points_at(&(1, 1).into(), Metric::Taxicab, usize::MAX).count(), 0);
+    }
+
+    #[test]
+    fn test_line() {
+        let grid = Grid::full(3, 4, 0u8);
+        assert_eq!(grid.line(&(0, 0).into(), &(2, 2).into()).count(), 3);
+        assert_eq!(grid.line(&(0, 0).into(), &(1, 3).into()).collect_vec(),
+                   [(0, 0).into(), (0, 1).into(), (1, 2).into(), (1, 3).into()]);
+    }
+}
@@ -0,0 +1,225 @@
+use std::iter;
+use std::ops::{Index, IndexMut};
+use itertools::Itertools;
+use super::UCoordinate;
+use super::grid::Grid;
+
+/// Dense N-dimensional array, stored with the last axis varying fastest (so a `GridN<T, 2>` is laid
+/// out like a `Grid<T>` indexed by `[row, col]`)
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct GridN<T, const N: usize> {
+    data: Vec<T>,
+    shape: [usize; N],
+}
+
+impl<T: Clone, const N: usize> GridN<T, N> {
+    pub fn full(shape: [usize; N], value: T) -> Self {
+        assert!(N > 0);
+        assert!(shape.iter().all(|s| *s > 0));
+        Self { data: vec![value; shape.iter().product()], shape }
+    }
+
+    /// The N-1 dimensional grid at `index` along `axis`. `M` must be `N - 1` and `N` at least 2;
+    /// both are checked at compile time:
+    ///
+    /// ```compile_fail
+    /// use aoc_2023::coordinate::grid_n::GridN;
+    /// let cube = GridN::full([2, 2, 2], 0u8);
+    /// let wrong: GridN<u8, 1> = cube.slice::<1>(0, 0);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use aoc_2023::coordinate::grid_n::GridN;
+    /// let line = GridN::full([3], 0u8);
+    /// let point: GridN<u8, 0> = line.slice::<0>(0, 0);
+    /// ```
+    pub fn slice<const M: usize>(&self, axis: usize, index: usize) -> GridN<T, M> {
+        const {
+            assert!(N >= 2, "only grids of two or more dimensions can be sliced");
+            assert!(M + 1 == N, "slice must drop exactly one dimension");
+        }
+        assert!(index < self.shape[axis], "index {index} out of bounds for axis {axis}");
+        let shape: [usize; M] = drop_axis(&self.shape, axis);
+        let data = GridN::<T, M>::idxs_for_shape(shape)
+            .map(|idx| self[insert_axis(&idx, axis, index)].clone())
+            .collect();
+        GridN { data, shape }
+    }
+}
+
+impl<T, const N: usize> GridN<T, N> {
+    pub fn from_fn(shape: [usize; N], f: impl FnMut(UCoordinate<N>) -> T) -> Self {
+        assert!(N > 0);
+        assert!(shape.iter().all(|s| *s > 0));
+        Self { data: Self::idxs_for_shape(shape).map(f).collect(), shape }
+    }
+
+    pub fn shape(&self) -> [usize; N] {
+        self.shape
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn is_in_bounds(&self, coordinate: &UCoordinate<N>) -> bool {
+        coordinate.iter().zip_eq(self.shape.iter()).all(|(c, s)| c < s)
+    }
+
+    fn offset(&self, coordinate: &UCoordinate<N>) -> Option<usize> {
+        if !self.is_in_bounds(coordinate) {
+            return None;
+        }
+        Some(coordinate.iter().zip_eq(self.shape.iter())
+            .fold(0, |acc, (c, s)| acc * s + c))
+    }
+
+    pub fn get(&self, coordinate: &UCoordinate<N>) -> Option<&T> {
+        self.offset(coordinate).map(|offset| &self.data[offset])
+    }
+
+    pub fn get_mut(&mut self, coordinate: &UCoordinate<N>) -> Option<&mut T> {
+        self.offset(coordinate).map(|offset| &mut self.data[offset])
+    }
+
+    /// In-bounds coordinates differing from `coordinate` by one along a single axis
+    pub fn neighbors(&self, coordinate: &UCoordinate<N>) -> impl Iterator<Item=UCoordinate<N>> + '_ {
+        let coordinate = *coordinate;
+        (0..N).cartesian_product([false, true])
+            .filter_map(move |(axis, up)| {
+                let mut result = coordinate;
+                result[axis] = if up {
+                    result[axis].checked_add(1)?
+                } else {
+                    result[axis].checked_sub(1)?
+                };
+                Some(result).filter(|r| self.is_in_bounds(r))
+            })
+    }
+
+    fn idxs_for_shape(shape: [usize; N]) -> impl Iterator<Item=UCoordinate<N>> {
+        let mut next = Some(UCoordinate::origin());
+        iter::from_fn(move || {
+            let current = next?;
+            let mut advanced = current;
+            next = None;
+            for axis in (0..N).rev() {
+                advanced[axis] += 1;
+                if advanced[axis] < shape[axis] {
+                    next = Some(advanced);
+                    break;
+                }
+                advanced[axis] = 0;
+            }
+            Some(current)
+        })
+    }
+
+    /// All in-bounds coordinates, in storage order
+    pub fn iter_idxs(&self) -> impl Iterator<Item=UCoordinate<N>> {
+        Self::idxs_for_shape(self.shape)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&T> + '_ {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=&mut T> + '_ {
+        self.data.iter_mut()
+    }
+}
+
+impl<T: Clone> GridN<T, 3> {
+    /// The 2-D grid at `index` along `axis`, with the remaining axes in their original order
+    pub fn layer(&self, axis: usize, index: usize) -> Grid<T> {
+        self.slice::<2>(axis, index).into()
+    }
+}
+
+fn drop_axis<const N: usize, const M: usize>(coordinate: &[usize; N], axis: usize) -> [usize; M] {
+    let mut result = [0; M];
+    for (target, value) in result.iter_mut()
+        .zip_eq(coordinate.iter().enumerate().filter(|(a, _)| *a != axis).map(|(_, v)| v)) {
+        *target = *value;
+    }
+    result
+}
+
+fn insert_axis<const N: usize, const M: usize>(
+    coordinate: &UCoordinate<M>,
+    axis: usize,
+    value: usize,
+) -> UCoordinate<N> {
+    let mut result = [value; N];
+    for (target, v) in result.iter_mut().enumerate()
+        .filter(|(a, _)| *a != axis).map(|(_, t)| t)
+        .zip_eq(coordinate.iter()) {
+        *target = *v;
+    }
+    result.into()
+}
+
+impl<T, const N: usize> Index<UCoordinate<N>> for GridN<T, N> {
+    type Output = T;
+
+    fn index(&self, index: UCoordinate<N>) -> &Self::Output {
+        let offset = self.offset(&index)
+            .unwrap_or_else(|| panic!("{index:?} out of bounds for shape {:?}", self.shape));
+        &self.data[offset]
+    }
+}
+
+impl<T, const N: usize> IndexMut<UCoordinate<N>> for GridN<T, N> {
+    fn index_mut(&mut self, index: UCoordinate<N>) -> &mut Self::Output {
+        let offset = self.offset(&index)
+            .unwrap_or_else(|| panic!("{index:?} out of bounds for shape {:?}", self.shape));
+        &mut self.data[offset]
+    }
+}
+
+impl<T> From<GridN<T, 2>> for Grid<T> {
+    fn from(value: GridN<T, 2>) -> Self {
+        let [rows, cols] = value.shape;
+        let mut data = value.data.into_iter();
+        let vecs = iter::repeat_with(|| data.by_ref().take(cols).collect())
+            .take(rows)
+            .collect();
+        Grid::try_from_vec_of_vecs(vecs).expect("GridN is never empty")
+    }
+}
+
+impl<T> From<Grid<T>> for GridN<T, 2> {
+    fn from(value: Grid<T>) -> Self {
+        let shape = [value.rows(), value.cols()];
+        let data = value.to_vec_of_vecs().into_iter().flatten().collect();
+        Self { data, shape }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_layer() {
+        let grid = GridN::from_fn([2, 3, 4], |idx| idx[0] * 100 + idx[1] * 10 + idx[2]);
+        assert_eq!(grid[UCoordinate::new([1, 2, 3])], 123);
+        assert_eq!(grid.layer(2, 1).to_vec_of_vecs(), vec![
+            vec![1, 11, 21],
+            vec![101, 111, 121],
+        ]);
+        assert_eq!(grid.iter_idxs().count(), 24);
+        assert!(grid.iter_idxs().all(|idx| grid[idx] == idx[0] * 100 + idx[1] * 10 + idx[2]));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = GridN::full([2, 3, 4], 0u8);
+        assert_eq!(grid.neighbors(&UCoordinate::origin()).count(), 3);
+        assert_eq!(grid.neighbors(&UCoordinate::new([1, 1, 1])).count(), 5);
+    }
+}
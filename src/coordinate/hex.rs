@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Write};
+use std::iter;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+use itertools::Itertools;
+use super::ICoordinate;
+
+/// The six neighbors of a hex, named for a pointy-top layout (rows of hexes staggered
+/// horizontally), in counter-clockwise order starting from East
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East, HexDirection::NorthEast, HexDirection::NorthWest,
+        HexDirection::West, HexDirection::SouthWest, HexDirection::SouthEast,
+    ];
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 3) % 6]
+    }
+
+    pub const fn rotate_clockwise(self) -> Self {
+        Self::ALL[(self as usize + 5) % 6]
+    }
+
+    pub const fn rotate_counter_clockwise(self) -> Self {
+        Self::ALL[(self as usize + 1) % 6]
+    }
+}
+
+/// The six neighbors of a hex in a flat-top layout (columns of hexes staggered vertically), in
+/// counter-clockwise order starting from NorthEast
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum FlatHexDirection {
+    NorthEast,
+    North,
+    NorthWest,
+    SouthWest,
+    South,
+    SouthEast,
+}
+
+impl FlatHexDirection {
+    pub const ALL: [FlatHexDirection; 6] = [
+        FlatHexDirection::NorthEast, FlatHexDirection::North, FlatHexDirection::NorthWest,
+        FlatHexDirection::SouthWest, FlatHexDirection::South, FlatHexDirection::SouthEast,
+    ];
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 3) % 6]
+    }
+
+    pub const fn rotate_clockwise(self) -> Self {
+        Self::ALL[(self as usize + 5) % 6]
+    }
+
+    pub const fn rotate_counter_clockwise(self) -> Self {
+        Self::ALL[(self as usize + 1) % 6]
+    }
+}
+
+/// Flat-top names: ne, n, nw, sw, s, se
+impl FromStr for FlatHexDirection {
+    type Err = HexParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "ne" => FlatHexDirection::NorthEast,
+            "n" => FlatHexDirection::North,
+            "nw" => FlatHexDirection::NorthWest,
+            "sw" => FlatHexDirection::SouthWest,
+            "s" => FlatHexDirection::South,
+            "se" => FlatHexDirection::SouthEast,
+            _ => return Err(HexParseError::Unrecognized(s.to_owned())),
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum HexParseError {
+    #[error("unrecognized hex direction {0:?}")]
+    Unrecognized(String),
+}
+
+/// Pointy-top names: e, ne, nw, w, sw, se
+impl FromStr for HexDirection {
+    type Err = HexParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "e" => HexDirection::East,
+            "ne" => HexDirection::NorthEast,
+            "nw" => HexDirection::NorthWest,
+            "w" => HexDirection::West,
+            "sw" => HexDirection::SouthWest,
+            "se" => HexDirection::SouthEast,
+            _ => return Err(HexParseError::Unrecognized(s.to_owned())),
+        })
+    }
+}
+
+/// How hexes are arranged into rows and columns when stored in a rectangular grid. The `R`
+/// layouts are pointy-top and shift every odd or even row right by half a hex; the `Q` layouts are
+/// flat-top and shift columns down.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+/// Axial coordinates (q, r); the implied third cube coordinate is s = -q - r
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash, Default)]
+pub struct HexCoordinate {
+    q: isize,
+    r: isize,
+}
+
+impl HexCoordinate {
+    pub const fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    pub const fn origin() -> Self {
+        Self::new(0, 0)
+    }
+
+    pub const fn q(&self) -> isize {
+        self.q
+    }
+
+    pub const fn r(&self) -> isize {
+        self.r
+    }
+
+    pub const fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub const fn cube(&self) -> [isize; 3] {
+        [self.q, self.r, self.s()]
+    }
+
+    /// None unless the components sum to zero
+    pub const fn from_cube([q, r, s]: [isize; 3]) -> Option<Self> {
+        if q + r + s == 0 {
+            Some(Self::new(q, r))
+        } else {
+            None
+        }
+    }
+
+    pub const fn unit(direction: HexDirection) -> Self {
+        match direction {
+            HexDirection::East => Self::new(1, 0),
+            HexDirection::NorthEast => Self::new(1, -1),
+            HexDirection::NorthWest => Self::new(0, -1),
+            HexDirection::West => Self::new(-1, 0),
+            HexDirection::SouthWest => Self::new(-1, 1),
+            HexDirection::SouthEast => Self::new(0, 1),
+        }
+    }
+
+    /// Flat-top neighbors in the same axial coordinates as `unit`
+    pub const fn flat_unit(direction: FlatHexDirection) -> Self {
+        match direction {
+            FlatHexDirection::NorthEast => Self::new(1, -1),
+            FlatHexDirection::North => Self::new(0, -1),
+            FlatHexDirection::NorthWest => Self::new(-1, 0),
+            FlatHexDirection::SouthWest => Self::new(-1, 1),
+            FlatHexDirection::South => Self::new(0, 1),
+            FlatHexDirection::SouthEast => Self::new(1, 0),
+        }
+    }
+
+    pub fn distance(&self, other: &Self) -> usize {
+        let diff = *self - *other;
+        diff.cube().iter().map(|x| x.unsigned_abs()).max().expect("three components")
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item=(HexDirection, HexCoordinate)> {
+        let center = *self;
+        HexDirection::ALL.into_iter().map(move |d| (d, center + d))
+    }
+
+    /// Every hex at exactly `radius` from `self`; just `self` for radius 0
+    pub fn ring(&self, radius: usize) -> impl Iterator<Item=HexCoordinate> {
+        let mut current = *self + Self::unit(HexDirection::SouthWest) * radius as isize;
+        let steps = HexDirection::ALL.into_iter()
+            .flat_map(move |d| iter::repeat_n(d, radius));
+        let center = iter::once(*self).filter(move |_| radius == 0);
+        center.chain(steps.map(move |d| {
+            let result = current;
+            current += d;
+            result
+        }))
+    }
+
+    /// `self`, then rings 1 through `radius` in order
+    pub fn spiral(&self, radius: usize) -> impl Iterator<Item=HexCoordinate> {
+        let center = *self;
+        (0..=radius).flat_map(move |r| center.ring(r))
+    }
+
+    /// Rotate 60 degrees clockwise about the origin, as drawn with rows increasing downward
+    pub const fn rotate_clockwise(self) -> Self {
+        let [_, r, s] = self.cube();
+        Self::new(-r, -s)
+    }
+
+    pub const fn rotate_counter_clockwise(self) -> Self {
+        let [q, _, s] = self.cube();
+        Self::new(-s, -q)
+    }
+
+    /// Rotate clockwise by `sixth_turns` sixths of a full turn about `center`
+    pub fn rotate_around(&self, center: &Self, sixth_turns: usize) -> Self {
+        let mut relative = *self - *center;
+        for _ in 0..sixth_turns % 6 {
+            relative = relative.rotate_clockwise();
+        }
+        relative + *center
+    }
+
+    /// As `[row, col]`
+    pub const fn to_offset(&self, layout: OffsetLayout) -> ICoordinate<2> {
+        let Self { q, r } = *self;
+        ICoordinate::new(match layout {
+            OffsetLayout::OddR => [r, q + (r - (r & 1)) / 2],
+            OffsetLayout::EvenR => [r, q + (r + (r & 1)) / 2],
+            OffsetLayout::OddQ => [r + (q - (q & 1)) / 2, q],
+            OffsetLayout::EvenQ => [r + (q + (q & 1)) / 2, q],
+        })
+    }
+
+    /// From `[row, col]`
+    pub fn from_offset(offset: &ICoordinate<2>, layout: OffsetLayout) -> Self {
+        let [row, col] = **offset;
+        match layout {
+            OffsetLayout::OddR => Self::new(col - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => Self::new(col - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => Self::new(col, row - (col - (col & 1)) / 2),
+            OffsetLayout::EvenQ => Self::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+}
+
+impl Add for HexCoordinate {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for HexCoordinate {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for HexCoordinate {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<isize> for HexCoordinate {
+    type Output = Self;
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Add<HexDirection> for HexCoordinate {
+    type Output = Self;
+    fn add(self, rhs: HexDirection) -> Self::Output {
+        self + Self::unit(rhs)
+    }
+}
+
+impl AddAssign<HexDirection> for HexCoordinate {
+    fn add_assign(&mut self, rhs: HexDirection) {
+        *self = *self + rhs;
+    }
+}
+
+impl Add<FlatHexDirection> for HexCoordinate {
+    type Output = Self;
+    fn add(self, rhs: FlatHexDirection) -> Self::Output {
+        self + Self::flat_unit(rhs)
+    }
+}
+
+impl AddAssign<FlatHexDirection> for HexCoordinate {
+    fn add_assign(&mut self, rhs: FlatHexDirection) {
+        *self = *self + rhs;
+    }
+}
+
+/// Sparse map from hexes to values
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct HexGrid<T> {
+    cells: HashMap<HexCoordinate, T>,
+}
+
+impl<T> HexGrid<T> {
+    pub fn new() -> Self {
+        Self { cells: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, coordinate: &HexCoordinate) -> Option<&T> {
+        self.cells.get(coordinate)
+    }
+
+    pub fn get_mut(&mut self, coordinate: &HexCoordinate) -> Option<&mut T> {
+        self.cells.get_mut(coordinate)
+    }
+
+    pub fn insert(&mut self, coordinate: HexCoordinate, value: T) -> Option<T> {
+        self.cells.insert(coordinate, value)
+    }
+
+    pub fn remove(&mut self, coordinate: &HexCoordinate) -> Option<T> {
+        self.cells.remove(coordinate)
+    }
+
+    pub fn contains(&self, coordinate: &HexCoordinate) -> bool {
+        self.cells.contains_key(coordinate)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&HexCoordinate, &T)> + '_ {
+        self.cells.iter()
+    }
+
+    /// Occupied neighbors of `coordinate`
+    pub fn neighbors<'a>(&'a self, coordinate: &HexCoordinate) -> impl Iterator<Item=(HexDirection, HexCoordinate, &'a T)> + 'a {
+        coordinate.neighbors()
+            .filter_map(move |(d, n)| self.cells.get(&n).map(|t| (d, n, t)))
+    }
+
+    /// Render the occupied bounding rows in `OddR` layout, with `empty` for missing cells. Odd
+    /// rows are indented by one character so neighbors line up diagonally.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char, empty: char) -> String {
+        let offsets: HashMap<_, _> = self.cells.iter()
+            .map(|(c, t)| (c.to_offset(OffsetLayout::OddR), t))
+            .collect();
+        let Some((min_row, max_row)) = offsets.keys().map(|o| o[0]).minmax().into_option() else {
+            return String::new();
+        };
+        let (min_col, max_col) = offsets.keys().map(|o| o[1]).minmax().into_option()
+            .expect("not empty");
+        let mut result = String::new();
+        for row in min_row..=max_row {
+            if row.rem_euclid(2) == 1 {
+                result.push(' ');
+            }
+            let line = (min_col..=max_col)
+                .map(|col| offsets.get(&ICoordinate::new([row, col])).map(|t| cell(t)).unwrap_or(empty))
+                .join(" ");
+            result.push_str(&line);
+            result.push('\n');
+        }
+        result
+    }
+}
+
+impl<T: Display> Display for HexGrid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rendered = self.render(|t| t.to_string().chars().next().unwrap_or(' '), '.');
+        for c in rendered.chars() {
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromIterator<(HexCoordinate, T)> for HexGrid<T> {
+    fn from_iter<I: IntoIterator<Item=(HexCoordinate, T)>>(iter: I) -> Self {
+        Self { cells: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_distance_and_rings() {
+        let center = HexCoordinate::new(2, -1);
+        for radius in 0..4 {
+            let ring = center.ring(radius).collect_vec();
+            assert_eq!(ring.len(), if radius == 0 { 1 } else { 6 * radius });
+            assert!(ring.iter().all(|h| h.distance(&center) == radius));
+            assert_eq!(ring.iter().unique().count(), ring.len());
+        }
+        assert_eq!(center.spiral(2).count(), 19);
+        let path: HexCoordinate = ["ne", "ne", "s", "s"].iter()
+            .map(|s| s.parse::<FlatHexDirection>().unwrap())
+            .fold(HexCoordinate::origin(), |acc, d| acc + d);
+        assert_eq!(path.distance(&HexCoordinate::origin()), 2);
+        for d in FlatHexDirection::ALL {
+            assert_eq!(HexCoordinate::flat_unit(d.rotate_clockwise()),
+                       HexCoordinate::flat_unit(d).rotate_clockwise());
+            assert_eq!(HexCoordinate::origin() + d + d.opposite(), HexCoordinate::origin());
+        }
+    }
+
+    #[test]
+    fn test_rotation_and_offsets() {
+        let h = HexCoordinate::new(3, -1);
+        assert_eq!(HexCoordinate::unit(HexDirection::East).rotate_clockwise(),
+                   HexCoordinate::unit(HexDirection::SouthEast));
+        assert_eq!(h.rotate_clockwise().rotate_counter_clockwise(), h);
+        assert_eq!(h.rotate_around(&HexCoordinate::new(1, 1), 6), h);
+        for layout in [OffsetLayout::OddR, OffsetLayout::EvenR, OffsetLayout::OddQ, OffsetLayout::EvenQ] {
+            for c in HexCoordinate::origin().spiral(3) {
+                assert_eq!(HexCoordinate::from_offset(&c.to_offset(layout), layout), c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let grid: HexGrid<char> = HexCoordinate::origin().ring(1).map(|h| (h, '#')).collect();
+        assert_eq!(grid.to_string(), " # # .\n# . #\n # # .\n");
+        assert_eq!(grid.neighbors(&HexCoordinate::origin()).count(), 6);
+    }
+}
@@ -0,0 +1,244 @@
+use std::array;
+use std::ops::RangeInclusive;
+use crate::number_theory::Fraction;
+use super::ICoordinate;
+use super::projection::XY;
+
+/// A point moving in a straight line: `position + velocity * t`. Restricting `t` gives rays
+/// (`t >= 0`) and segments (`0 <= t <= 1` when built with `Trajectory::segment`). All arithmetic is
+/// done exactly in `i128`. A zero velocity gives a stationary point, which counts as being at its
+/// position at t = 0 when it meets something.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Trajectory<const N: usize> {
+    position: ICoordinate<N>,
+    velocity: ICoordinate<N>,
+}
+
+/// Where and when two trajectories meet
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Crossing<const N: usize> {
+    pub point: [Fraction; N],
+    pub t_self: Fraction,
+    pub t_other: Fraction,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Intersection<const N: usize> {
+    Point(Crossing<N>),
+    /// Parallel and distinct, or a stationary point off the other line
+    Parallel,
+    /// The same line, so infinitely many common points
+    Coincident,
+    /// Not parallel but never meet (3-D only)
+    Skew,
+}
+
+impl<const N: usize> Crossing<N> {
+    pub fn in_future_for_both(&self) -> bool {
+        !self.t_self.is_negative() && !self.t_other.is_negative()
+    }
+
+    pub fn within_segments(&self) -> bool {
+        let unit = Fraction::from(0)..=Fraction::from(1);
+        unit.contains(&self.t_self) && unit.contains(&self.t_other)
+    }
+
+    /// Every component of the point lies within `bounds`
+    pub fn within(&self, bounds: &RangeInclusive<i128>) -> bool {
+        let bounds = Fraction::from(*bounds.start())..=Fraction::from(*bounds.end());
+        self.point.iter().all(|c| bounds.contains(c))
+    }
+}
+
+impl<const N: usize> Intersection<N> {
+    pub fn crossing(&self) -> Option<&Crossing<N>> {
+        match self {
+            Intersection::Point(crossing) => Some(crossing),
+            _ => None,
+        }
+    }
+}
+
+impl<const N: usize> Trajectory<N> {
+    pub fn new(position: ICoordinate<N>, velocity: ICoordinate<N>) -> Self {
+        Self { position, velocity }
+    }
+
+    /// From `start` at t = 0 to `end` at t = 1
+    pub fn segment(start: ICoordinate<N>, end: ICoordinate<N>) -> Self {
+        Self::new(start, end - start)
+    }
+
+    pub fn position(&self) -> ICoordinate<N> {
+        self.position
+    }
+
+    pub fn velocity(&self) -> ICoordinate<N> {
+        self.velocity
+    }
+
+    pub fn at(&self, t: Fraction) -> [Fraction; N] {
+        let (p, v) = (wide(&self.position), wide(&self.velocity));
+        array::from_fn(|axis| Fraction::new(p[axis] * t.den() + v[axis] * t.num(), t.den()))
+    }
+
+    pub fn is_stationary(&self) -> bool {
+        self.velocity.iter().all(|v| *v == 0)
+    }
+
+    fn crossing(&self, t_num: i128, s_num: i128, den: i128) -> Crossing<N> {
+        let t_self = Fraction::new(t_num, den);
+        Crossing { point: self.at(t_self), t_self, t_other: Fraction::new(s_num, den) }
+    }
+
+    /// None unless one of the two is stationary. `parallel` tells whether two vectors are.
+    fn intersect_stationary(
+        &self,
+        other: &Self,
+        parallel: impl Fn(&[i128; N], &[i128; N]) -> bool,
+    ) -> Option<Intersection<N>> {
+        let d = sub(&wide(&other.position), &wide(&self.position));
+        let (v1, v2) = (wide(&self.velocity), wide(&other.velocity));
+        let meets = |v: &[i128; N]| parallel(&d, v);
+        Some(match (self.is_stationary(), other.is_stationary()) {
+            (false, false) => return None,
+            (true, true) if d == [0; N] => Intersection::Coincident,
+            (true, true) => Intersection::Parallel,
+            (true, false) if meets(&v2) => Intersection::Point(self.crossing(0, -dot(&d, &v2), dot(&v2, &v2))),
+            (false, true) if meets(&v1) => {
+                let t_self = Fraction::new(dot(&d, &v1), dot(&v1, &v1));
+                Intersection::Point(Crossing { point: self.at(t_self), t_self, t_other: Fraction::from(0) })
+            },
+            _ => Intersection::Parallel,
+        })
+    }
+}
+
+impl Trajectory<2> {
+    pub fn intersect(&self, other: &Self) -> Intersection<2> {
+        if let Some(stationary) = self.intersect_stationary(other, |a, b| cross_2(a, b) == 0) {
+            return stationary;
+        }
+        let d = sub(&wide(&other.position), &wide(&self.position));
+        let (v1, v2) = (wide(&self.velocity), wide(&other.velocity));
+        let den = cross_2(&v1, &v2);
+        if den == 0 {
+            return if cross_2(&d, &v1) == 0 { Intersection::Coincident } else { Intersection::Parallel };
+        }
+        Intersection::Point(self.crossing(cross_2(&d, &v2), cross_2(&d, &v1), den))
+    }
+}
+
+impl Trajectory<3> {
+    pub fn intersect(&self, other: &Self) -> Intersection<3> {
+        if let Some(stationary) = self.intersect_stationary(other, |a, b| cross_3(a, b) == [0; 3]) {
+            return stationary;
+        }
+        let d = sub(&wide(&other.position), &wide(&self.position));
+        let (v1, v2) = (wide(&self.velocity), wide(&other.velocity));
+        let w = cross_3(&v1, &v2);
+        if w == [0; 3] {
+            return if cross_3(&d, &v1) == [0; 3] {
+                Intersection::Coincident
+            } else {
+                Intersection::Parallel
+            };
+        }
+        if dot(&d, &w) != 0 {
+            return Intersection::Skew;
+        }
+        Intersection::Point(self.crossing(
+            dot(&cross_3(&d, &v2), &w),
+            dot(&cross_3(&d, &v1), &w),
+            dot(&w, &w),
+        ))
+    }
+
+    /// Ignore the third axis
+    pub fn project_xy(&self) -> Trajectory<2> {
+        Trajectory::new(XY.project(&self.position), XY.project(&self.velocity))
+    }
+}
+
+fn wide<const N: usize>(c: &ICoordinate<N>) -> [i128; N] {
+    c.map(|x| x as i128)
+}
+
+fn sub<const N: usize>(a: &[i128; N], b: &[i128; N]) -> [i128; N] {
+    let mut result = *a;
+    for (target, value) in result.iter_mut().zip(b.iter()) {
+        *target -= value;
+    }
+    result
+}
+
+fn dot<const N: usize>(a: &[i128; N], b: &[i128; N]) -> i128 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn cross_2(a: &[i128; 2], b: &[i128; 2]) -> i128 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn cross_3(a: &[i128; 3], b: &[i128; 3]) -> [i128; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hail(p: [isize; 3], v: [isize; 3]) -> Trajectory<3> {
+        Trajectory::new(p.into(), v.into())
+    }
+
+    #[test]
+    fn test_hailstone_example() {
+        let a = hail([19, 13, 30], [-2, 1, -2]).project_xy();
+        let b = hail([18, 19, 22], [-1, -1, -2]).project_xy();
+        let crossing = *a.intersect(&b).crossing().unwrap();
+        assert_eq!(crossing.point, [Fraction::new(43, 3), Fraction::new(46, 3)]);
+        assert!(crossing.in_future_for_both());
+        assert!(crossing.within(&(7..=27)));
+
+        let c = hail([20, 25, 34], [-2, -2, -4]).project_xy();
+        assert_eq!(b.intersect(&c), Intersection::Parallel);
+
+        let d = hail([20, 19, 15], [1, -5, -3]).project_xy();
+        assert!(!a.intersect(&d).crossing().unwrap().in_future_for_both());
+    }
+
+    #[test]
+    fn test_3d_and_segments() {
+        let a = hail([0, 0, 0], [1, 1, 1]);
+        assert_eq!(a.intersect(&hail([2, 0, 0], [-1, 1, 1])).crossing().unwrap().point,
+                   [Fraction::from(1); 3]);
+        assert_eq!(a.intersect(&hail([0, 1, 0], [1, 0, 0])), Intersection::Skew);
+        assert_eq!(a.intersect(&hail([2, 2, 2], [-3, -3, -3])), Intersection::Coincident);
+
+        let s1 = Trajectory::segment(ICoordinate::new([0, 0]), ICoordinate::new([4, 4]));
+        let s2 = Trajectory::segment(ICoordinate::new([0, 4]), ICoordinate::new([1, 3]));
+        let crossing = *s1.intersect(&s2).crossing().unwrap();
+        assert!(!crossing.within_segments());
+        assert_eq!(crossing.t_self, Fraction::new(1, 2));
+
+        // Zero velocities are points that sit still
+        let point = Trajectory::segment(ICoordinate::new([2, 2]), ICoordinate::new([2, 2]));
+        let crossing = *s1.intersect(&point).crossing().unwrap();
+        assert_eq!((crossing.t_self, crossing.t_other), (Fraction::new(1, 2), Fraction::from(0)));
+        assert!(crossing.within_segments());
+        assert_eq!(point.intersect(&s1).crossing().unwrap().t_other, Fraction::new(1, 2));
+        assert_eq!(point.intersect(&s2).crossing().unwrap().t_other, Fraction::from(2));
+        let off = Trajectory::segment(ICoordinate::new([0, 0]), ICoordinate::new([0, 4]));
+        assert_eq!(point.intersect(&off), Intersection::Parallel);
+        assert_eq!(point.intersect(&point), Intersection::Coincident);
+        let falling = hail([1, 2, 3], [0, 0, -1]).project_xy();
+        assert!(falling.is_stationary());
+        assert_eq!(falling.intersect(&hail([0, 0, 0], [1, 2, 5]).project_xy()).crossing().unwrap().t_other,
+                   Fraction::from(1));
+    }
+}
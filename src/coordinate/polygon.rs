@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+use crate::number_theory::gcd;
+use crate::util::CheckedAdd;
+use super::{Direction, ICoordinate, twice_shoelace_in, twice_signed_shoelace};
+
+type Point = ICoordinate<2>;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Orientation {
+    /// As drawn with rows increasing downward, e.g. East then South
+    Clockwise,
+    CounterClockwise,
+    /// Zero area
+    Degenerate,
+}
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum PolygonError {
+    #[error("steps must return to the starting point")]
+    NotClosed,
+    #[error("step of length {0} leaves the coordinate range")]
+    StepTooLong(usize),
+}
+
+/// A simple polygon on the integer lattice, given by its vertices in order
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn from_vertices(vertices: impl IntoIterator<Item=Point>) -> Self {
+        Self { vertices: vertices.into_iter().collect() }
+    }
+
+    /// Walk from the origin; the final step must return to the origin
+    pub fn try_from_steps(
+        steps: impl IntoIterator<Item=(Direction, usize)>
+    ) -> Result<Self, PolygonError> {
+        let mut current = Point::origin();
+        let mut vertices = vec![];
+        for (direction, length) in steps {
+            current = isize::try_from(length).ok()
+                .and_then(|length| current.checked_add(&(Point::from(direction) * length)))
+                .ok_or(PolygonError::StepTooLong(length))?;
+            vertices.push(current);
+        }
+        if current != Point::origin() {
+            return Err(PolygonError::NotClosed);
+        }
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item=(Point, Point)> + '_ {
+        self.vertices.iter().copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    pub fn twice_area(&self) -> usize {
+        twice_shoelace_in::<i128, _>(self.vertices.iter().copied())
+            .try_into().expect("area overflows usize")
+    }
+
+    /// None if the area is not a whole number
+    pub fn area(&self) -> Option<usize> {
+        let twice_area = self.twice_area();
+        twice_area.is_multiple_of(2).then_some(twice_area / 2)
+    }
+
+    /// Number of lattice points on the boundary. This is only the perimeter when every edge is
+    /// axis-aligned; a diagonal edge counts just the lattice points it passes through.
+    pub fn boundary_points(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| gcd(a[0].abs_diff(b[0]) as u64, a[1].abs_diff(b[1]) as u64) as usize)
+            .sum()
+    }
+
+    /// Euclidean length of the boundary
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .map(|(a, b)| (a[0].abs_diff(b[0]) as f64).hypot(a[1].abs_diff(b[1]) as f64))
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, by Pick's theorem
+    pub fn interior_points(&self) -> usize {
+        (self.twice_area() + 2)
+            .checked_sub(self.boundary_points())
+            .expect("polygon is not simple")
+            / 2
+    }
+
+    /// Lattice points inside or on the boundary - the number of cells dug out when the boundary is
+    /// traced through cell centers
+    pub fn covered_cells(&self) -> usize {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match twice_signed_shoelace(self.vertices.iter().copied()) {
+            0 => Orientation::Degenerate,
+            a if a < 0 => Orientation::Clockwise,
+            _ => Orientation::CounterClockwise,
+        }
+    }
+
+    pub fn on_boundary(&self, point: &Point) -> bool {
+        self.edges().any(|(a, b)| {
+            let cross = compare_products(
+                b[0] as i128 - a[0] as i128, point[1] as i128 - a[1] as i128,
+                b[1] as i128 - a[1] as i128, point[0] as i128 - a[0] as i128,
+            );
+            cross == Ordering::Equal
+                && (a[0].min(b[0])..=a[0].max(b[0])).contains(&point[0])
+                && (a[1].min(b[1])..=a[1].max(b[1])).contains(&point[1])
+        })
+    }
+
+    /// Inside or on the boundary
+    pub fn contains(&self, point: &Point) -> bool {
+        if self.on_boundary(point) {
+            return true;
+        }
+        // Crossing number of a ray from point towards increasing column
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a[0] > point[0]) == (b[0] > point[0]) {
+                continue;
+            }
+            // Column where the edge crosses point's row, compared without division
+            let d_row = b[0] as i128 - a[0] as i128;
+            let crossing = compare_products(
+                point[1] as i128 - a[1] as i128, d_row,
+                point[0] as i128 - a[0] as i128, b[1] as i128 - a[1] as i128,
+            );
+            if (d_row > 0 && crossing.is_lt()) || (d_row < 0 && crossing.is_gt()) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+/// Compares `a * b` with `c * d` exactly. Each operand is a difference of two `isize`s, so a
+/// product's magnitude always fits in a `u128` even when the product does not fit in an `i128`.
+fn compare_products(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let product = |x: i128, y: i128| {
+        let magnitude = x.unsigned_abs().checked_mul(y.unsigned_abs()).expect("operand wider than isize");
+        (magnitude != 0 && (x < 0) != (y < 0), magnitude)
+    };
+    match (product(a, b), product(c, d)) {
+        ((false, p), (false, q)) => p.cmp(&q),
+        ((true, p), (true, q)) => q.cmp(&p),
+        ((negative, _), _) => if negative { Ordering::Less } else { Ordering::Greater },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lagoon_example() {
+        use Direction::*;
+        let polygon = Polygon::try_from_steps([
+            (East, 6), (South, 5), (West, 2), (South, 2), (East, 2), (South, 2), (West, 5),
+            (North, 2), (West, 1), (North, 2), (East, 2), (North, 3), (West, 2), (North, 2),
+        ]).unwrap();
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.perimeter(), 38.0);
+        assert_eq!(polygon.covered_cells(), 62);
+        assert_eq!(polygon.orientation(), Orientation::Clockwise);
+        assert!(polygon.contains(&(1, 1).into()));
+        assert!(polygon.contains(&(0, 6).into()));
+        assert!(!polygon.contains(&(3, 0).into()));
+        assert!(!polygon.contains(&(6, 6).into()));
+    }
+
+    #[test]
+    fn test_triangle() {
+        let polygon = Polygon::from_vertices([(0, 0).into(), (0, 4).into(), (3, 0).into()]);
+        assert_eq!(polygon.area(), Some(6));
+        assert_eq!(polygon.boundary_points(), 8);
+        assert_eq!(polygon.perimeter(), 12.0);
+        assert_eq!(polygon.interior_points(), 3);
+        assert_eq!(polygon.orientation(), Orientation::Clockwise);
+        assert!(polygon.on_boundary(&(0, 2).into()));
+        assert!(polygon.contains(&(1, 1).into()));
+        assert!(!polygon.contains(&(2, 2).into()));
+    }
+
+    #[test]
+    fn test_extreme_vertices() {
+        // The hypotenuse runs along row + col == -1
+        let polygon = Polygon::from_vertices([
+            (isize::MIN, isize::MIN).into(), (isize::MIN, isize::MAX).into(), (isize::MAX, isize::MIN).into(),
+        ]);
+        assert!(polygon.on_boundary(&(0, -1).into()));
+        assert!(polygon.on_boundary(&(isize::MIN, 0).into()));
+        assert!(!polygon.on_boundary(&(0, -2).into()));
+        assert!(polygon.contains(&(0, -2).into()));
+        assert!(polygon.contains(&(isize::MIN + 1, isize::MIN + 1).into()));
+        assert!(!polygon.contains(&(0, 0).into()));
+        assert!(!polygon.contains(&(isize::MAX, isize::MAX).into()));
+    }
+
+    #[test]
+    fn test_bad_steps() {
+        use Direction::*;
+        assert_eq!(Polygon::try_from_steps([(East, 2), (North, 2)]), Err(PolygonError::NotClosed));
+        assert_eq!(Polygon::try_from_steps([(East, usize::MAX), (West, usize::MAX)]),
+                   Err(PolygonError::StepTooLong(usize::MAX)));
+        assert_eq!(Polygon::try_from_steps([(East, isize::MAX as usize), (East, 1)]),
+                   Err(PolygonError::StepTooLong(1)));
+    }
+}
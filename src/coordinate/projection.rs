@@ -0,0 +1,171 @@
+use super::{Coordinate, LatticePoint, Scalar};
+use super::aa_box::AaBox;
+
+/// An ordered choice of `M` distinct axes out of `N`. `axis_selection!` checks the axes at compile
+/// time; `try_new` checks them at runtime.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct AxisSelection<const N: usize, const M: usize>([usize; M]);
+
+pub const XY: AxisSelection<3, 2> = crate::axis_selection![0, 1];
+pub const XZ: AxisSelection<3, 2> = crate::axis_selection![0, 2];
+pub const YZ: AxisSelection<3, 2> = crate::axis_selection![1, 2];
+
+/// An `AxisSelection` of the given axes, with `N` and `M` taken from context. The selection is
+/// checked in a `const` block, so an invalid one fails to compile:
+///
+/// ```compile_fail
+/// use aoc_2023::coordinate::projection::AxisSelection;
+/// let repeated: AxisSelection<3, 2> = aoc_2023::axis_selection![0, 0];
+/// ```
+#[macro_export]
+macro_rules! axis_selection {
+    ($($axis:expr),+ $(,)?) => {
+        const {
+            match $crate::coordinate::projection::AxisSelection::try_new([$($axis),+]) {
+                Some(axes) => axes,
+                None => panic!("axes must be distinct and in range"),
+            }
+        }
+    };
+}
+
+impl<const N: usize, const M: usize> AxisSelection<N, M> {
+    /// None unless the axes are distinct and each less than `N`
+    pub const fn try_new(axes: [usize; M]) -> Option<Self> {
+        let mut i = 0;
+        while i < M {
+            if axes[i] >= N {
+                return None;
+            }
+            let mut j = 0;
+            while j < i {
+                if axes[i] == axes[j] {
+                    return None;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        Some(Self(axes))
+    }
+
+    pub const fn axes(&self) -> [usize; M] {
+        self.0
+    }
+
+    pub fn project<P: Project<N, M>>(&self, value: &P) -> P::Projected {
+        value.project_axes(self)
+    }
+
+    pub fn embed<E: Embed<N, M>>(&self, value: &E, fill: E::Fill) -> E::Embedded {
+        value.embed_axes(self, fill)
+    }
+
+    fn pick<T: Copy>(&self, data: &[T; N]) -> [T; M] {
+        self.0.map(|axis| data[axis])
+    }
+
+    fn place<T: Copy>(&self, data: &[T; M], fill: T) -> [T; N] {
+        let mut result = [fill; N];
+        for (axis, value) in self.0.iter().zip(data.iter()) {
+            result[*axis] = *value;
+        }
+        result
+    }
+}
+
+/// Drop down to the selected axes
+pub trait Project<const N: usize, const M: usize> {
+    type Projected;
+    fn project_axes(&self, axes: &AxisSelection<N, M>) -> Self::Projected;
+}
+
+/// Inverse of `Project`: put components on the selected axes and `fill` everywhere else
+pub trait Embed<const N: usize, const M: usize> {
+    type Fill;
+    type Embedded;
+    fn embed_axes(&self, axes: &AxisSelection<N, M>, fill: Self::Fill) -> Self::Embedded;
+}
+
+impl<T: Scalar, const N: usize, const M: usize> Project<N, M> for Coordinate<T, N> {
+    type Projected = Coordinate<T, M>;
+    fn project_axes(&self, axes: &AxisSelection<N, M>) -> Self::Projected {
+        Coordinate(axes.pick(&self.0))
+    }
+}
+
+impl<T: Scalar, const N: usize, const M: usize> Embed<N, M> for Coordinate<T, M> {
+    type Fill = T;
+    type Embedded = Coordinate<T, N>;
+    fn embed_axes(&self, axes: &AxisSelection<N, M>, fill: T) -> Self::Embedded {
+        Coordinate(axes.place(&self.0, fill))
+    }
+}
+
+impl<const N: usize, const M: usize, C: Project<N, M>> Project<N, M> for (C, C) {
+    type Projected = (C::Projected, C::Projected);
+    fn project_axes(&self, axes: &AxisSelection<N, M>) -> Self::Projected {
+        (self.0.project_axes(axes), self.1.project_axes(axes))
+    }
+}
+
+impl<const N: usize, const M: usize, C: Embed<N, M>> Embed<N, M> for (C, C)
+where C::Fill: Copy {
+    type Fill = C::Fill;
+    type Embedded = (C::Embedded, C::Embedded);
+    fn embed_axes(&self, axes: &AxisSelection<N, M>, fill: Self::Fill) -> Self::Embedded {
+        (self.0.embed_axes(axes, fill), self.1.embed_axes(axes, fill))
+    }
+}
+
+impl<const N: usize, const M: usize, C> Project<N, M> for AaBox<C>
+where C: LatticePoint + Project<N, M>, C::Projected: LatticePoint {
+    type Projected = AaBox<C::Projected>;
+    fn project_axes(&self, axes: &AxisSelection<N, M>) -> Self::Projected {
+        AaBox::new(self.start().project_axes(axes), self.end().project_axes(axes))
+    }
+}
+
+/// The embedded box is one lattice point thick on every axis not selected
+impl<const N: usize, const M: usize, C> Embed<N, M> for AaBox<C>
+where C: LatticePoint + Embed<N, M>, C::Fill: Copy, C::Embedded: LatticePoint {
+    type Fill = C::Fill;
+    type Embedded = AaBox<C::Embedded>;
+    fn embed_axes(&self, axes: &AxisSelection<N, M>, fill: Self::Fill) -> Self::Embedded {
+        AaBox::new(self.start().embed_axes(axes, fill), self.end().embed_axes(axes, fill))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::coordinate::{ICoordinate, UCoordinate};
+
+    #[test]
+    fn test_round_trip() {
+        let zx: AxisSelection<3, 2> = crate::axis_selection![2, 0];
+        let c = UCoordinate::new([1, 2, 3]);
+        assert_eq!(zx.project(&c), UCoordinate::new([3, 1]));
+        assert_eq!(zx.embed(&zx.project(&c), 9), UCoordinate::new([1, 9, 3]));
+        let i = ICoordinate::new([-1, -2, -3]);
+        assert_eq!(YZ.project(&(i, c.try_into().unwrap())), (
+            ICoordinate::new([-2, -3]), ICoordinate::new([2, 3]),
+        ));
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(AxisSelection::<3, 2>::try_new([2, 0]).map(|s| s.axes()), Some([2, 0]));
+        assert_eq!(AxisSelection::<3, 2>::try_new([0, 0]), None);
+        assert_eq!(AxisSelection::<3, 2>::try_new([0, 3]), None);
+        assert_eq!(AxisSelection::<2, 3>::try_new([0, 1, 0]), None);
+    }
+
+    #[test]
+    fn test_box_projection() {
+        let block = AaBox::new(UCoordinate::new([2, 0, 5]), UCoordinate::new([0, 0, 7]));
+        let shadow = XY.project(&block);
+        assert_eq!(shadow, AaBox::new(UCoordinate::new([0, 0]), UCoordinate::new([2, 0])));
+        assert_eq!(XY.embed(&shadow, 5).volume(), 3);
+    }
+}
@@ -0,0 +1,219 @@
+use std::{array, iter};
+use itertools::{Either, Itertools};
+use crate::number_theory::gcd;
+use super::ICoordinate;
+use super::aa_box::AaBox;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Metric {
+    /// Sum of the per-axis distances; `ICoordinate::taxicab_dist`
+    Taxicab,
+    /// Largest per-axis distance; `ICoordinate::king_dist`
+    King,
+}
+
+impl Metric {
+    pub fn dist<const N: usize>(self, a: &ICoordinate<N>, b: &ICoordinate<N>) -> usize {
+        match self {
+            Metric::Taxicab => a.taxicab_dist(b),
+            Metric::King => a.king_dist(b),
+        }
+    }
+}
+
+impl<const N: usize> ICoordinate<N> {
+    /// Every point exactly `distance` away under `metric`, leaving out any past the range of
+    /// `isize`. Points are generated as they are asked for.
+    pub fn sphere(&self, metric: Metric, distance: usize) -> impl Iterator<Item=ICoordinate<N>> {
+        let center = *self;
+        match metric {
+            Metric::Taxicab => Either::Left(taxicab_sphere(center, 0, distance as i128)),
+            Metric::King if distance == 0 => Either::Right(Either::Left(iter::once(center))),
+            // The shell is split by the first axis at full distance: axes before it stay strictly
+            // inside, the axis itself is on one face and later axes range over the whole box
+            Metric::King => Either::Right(Either::Right((0..N).cartesian_product([-1, 1])
+                .filter_map(move |(face_axis, side)| {
+                    let distance = distance as i128;
+                    let face = shifted(center[face_axis], side * distance)?;
+                    let [mut start, mut end] = [center; 2];
+                    for axis in 0..N {
+                        let reach = if axis < face_axis { distance - 1 } else { distance };
+                        (start[axis], end[axis]) = clamped_span(center[axis], reach);
+                    }
+                    (start[face_axis], end[face_axis]) = (face, face);
+                    Some(AaBox::new(start, end).iter())
+                })
+                .flatten())),
+        }
+    }
+
+    /// Every point at most `distance` away under `metric`, leaving out any past the range of
+    /// `isize`
+    pub fn ball(&self, metric: Metric, distance: usize) -> impl Iterator<Item=ICoordinate<N>> {
+        let center = *self;
+        match metric {
+            Metric::Taxicab => Either::Left((0..=distance).flat_map(move |d| center.sphere(metric, d))),
+            Metric::King => {
+                let [mut start, mut end] = [center; 2];
+                for axis in 0..N {
+                    (start[axis], end[axis]) = clamped_span(center[axis], distance as i128);
+                }
+                Either::Right(AaBox::new(start, end).iter())
+            },
+        }
+    }
+
+    /// Walks from `self` to `end`, both included, one king step at a time: each step moves one
+    /// along the longest axis and takes the other axes to the lattice point nearest the segment,
+    /// as Bresenham's algorithm does. Halves round up.
+    pub fn line_to(&self, end: &Self) -> impl Iterator<Item=ICoordinate<N>> {
+        let start = *self;
+        let diff: [i128; N] = array::from_fn(|axis| end[axis] as i128 - self[axis] as i128);
+        let steps = diff.iter().map(|d| d.unsigned_abs()).max().unwrap_or(0);
+        // Only the first point has `i == 0`, where any divisor gives no offset
+        let divisor = steps.max(1);
+        (0..=steps).map(move |i| {
+            let mut point = start;
+            for (axis, d) in diff.iter().enumerate() {
+                // Both factors are below 2^64, so the product fits without widening further
+                let scaled = d.unsigned_abs() * i;
+                let (whole, rest) = (scaled / divisor, scaled % divisor);
+                let offset = if *d >= 0 {
+                    (whole + u128::from(2 * rest >= divisor)) as i128
+                } else {
+                    -((whole + u128::from(2 * rest > divisor)) as i128)
+                };
+                point[axis] = shifted(start[axis], offset).expect("the walk stays between its ends");
+            }
+            point
+        })
+    }
+
+    /// Only the lattice points lying exactly on the segment from `self` to `end`, both included
+    pub fn lattice_points_to(&self, end: &Self) -> impl Iterator<Item=ICoordinate<N>> {
+        let start = *self;
+        let diff: [i128; N] = array::from_fn(|axis| end[axis] as i128 - self[axis] as i128);
+        let steps = diff.iter().fold(0, |acc, d| gcd(acc, d.unsigned_abs()));
+        let step = diff.map(|d| if steps == 0 { 0 } else { d / steps as i128 });
+        (0..=steps).map(move |i| {
+            let mut point = start;
+            for axis in 0..N {
+                point[axis] = shifted(start[axis], step[axis] * i as i128)
+                    .expect("lattice points stay between the ends");
+            }
+            point
+        })
+    }
+}
+
+/// `base + offset`, if that is still an `isize`
+fn shifted(base: isize, offset: i128) -> Option<isize> {
+    isize::try_from(base as i128 + offset).ok()
+}
+
+/// The ends of `base - reach ..= base + reach`, cut off at the range of `isize`
+fn clamped_span(base: isize, reach: i128) -> (isize, isize) {
+    let clamp = |value: i128| value.clamp(isize::MIN as i128, isize::MAX as i128) as isize;
+    (clamp(base as i128 - reach), clamp(base as i128 + reach))
+}
+
+/// Largest offset from `base` that stays an `isize` on at least one side
+fn room(base: isize) -> i128 {
+    (base as i128 - isize::MIN as i128).max(isize::MAX as i128 - base as i128)
+}
+
+/// Points from `current` onwards whose remaining axes are exactly `remaining` away in total
+fn taxicab_sphere<const N: usize>(
+    current: ICoordinate<N>,
+    axis: usize,
+    remaining: i128,
+) -> Box<dyn Iterator<Item=ICoordinate<N>>> {
+    let base = current[axis];
+    let with_offset = move |offset: i128| {
+        let mut point = current;
+        point[axis] = shifted(base, offset)?;
+        Some(point)
+    };
+    if axis == N - 1 {
+        return Box::new([remaining, -remaining].into_iter().dedup().filter_map(with_offset));
+    }
+    // Using more than this here leaves the range of `isize` on both sides, and using less leaves
+    // more than the later axes can take up
+    let reach = remaining.min(room(base));
+    let least = remaining - (axis + 1..N).map(|later| room(current[later])).sum::<i128>();
+    Box::new((least.max(0)..=reach).flat_map(move |used| [used, -used].into_iter().dedup()
+        .filter_map(with_offset)
+        .flat_map(move |point| taxicab_sphere(point, axis + 1, remaining - used))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_spheres_and_balls() {
+        let center = ICoordinate::new([5, -3]);
+        assert_eq!(center.sphere(Metric::Taxicab, 0).collect_vec(), [center]);
+        for d in 1..5 {
+            let sphere = center.sphere(Metric::Taxicab, d).collect_vec();
+            assert_eq!(sphere.len(), 4 * d);
+            assert!(sphere.iter().all(|p| p.taxicab_dist(&center) == d));
+            assert_eq!(center.sphere(Metric::King, d).count(), 8 * d);
+        }
+        assert_eq!(center.ball(Metric::Taxicab, 2).count(), 13);
+        assert_eq!(ICoordinate::new([0, 0, 0]).ball(Metric::King, 1).count(), 27);
+        assert_eq!(ICoordinate::new([0, 0, 0]).sphere(Metric::Taxicab, 1).count(), 6);
+    }
+
+    #[test]
+    fn test_king_shell_matches_ball() {
+        let center = ICoordinate::new([1, -2, 3]);
+        for d in 0..4 {
+            let shell = center.sphere(Metric::King, d).sorted().collect_vec();
+            let filtered = center.ball(Metric::King, d).filter(|p| p.king_dist(&center) == d).sorted().collect_vec();
+            assert_eq!(shell, filtered);
+        }
+    }
+
+    #[test]
+    fn test_shapes_at_the_edge() {
+        let edge = ICoordinate::new([isize::MAX, 0]);
+        assert_eq!(edge.sphere(Metric::Taxicab, 1).count(), 3);
+        assert_eq!(edge.sphere(Metric::King, 1).count(), 5);
+        assert_eq!(edge.ball(Metric::Taxicab, 1).count(), 4);
+        assert_eq!(edge.ball(Metric::King, 1).count(), 6);
+        // Huge shapes are only generated as far as they are read
+        assert_eq!(edge.sphere(Metric::Taxicab, usize::MAX).take(3).count(), 3);
+        assert_eq!(edge.sphere(Metric::King, usize::MAX).take(3).count(), 3);
+        assert_eq!(edge.ball(Metric::King, usize::MAX).take(3).count(), 3);
+
+        let [low, high] = [ICoordinate::new([isize::MIN, isize::MIN]), ICoordinate::new([isize::MAX, isize::MAX])];
+        assert_eq!(low.line_to(&high).take(2).collect_vec(), [low, ICoordinate::new([isize::MIN + 1, isize::MIN + 1])]);
+        assert_eq!(high.line_to(&low).take(2).collect_vec(), [high, ICoordinate::new([isize::MAX - 1, isize::MAX - 1])]);
+        assert_eq!(low.lattice_points_to(&high).take(2).collect_vec(),
+                   [low, ICoordinate::new([isize::MIN + 1, isize::MIN + 1])]);
+    }
+
+    #[test]
+    fn test_line_to() {
+        let start = ICoordinate::new([0, 0]);
+        assert_eq!(start.line_to(&ICoordinate::new([1, 2])).collect_vec(), [
+            ICoordinate::new([0, 0]), ICoordinate::new([1, 1]), ICoordinate::new([1, 2]),
+        ]);
+        assert_eq!(start.line_to(&ICoordinate::new([-2, 5])).collect_vec(), [
+            ICoordinate::new([0, 0]), ICoordinate::new([0, 1]), ICoordinate::new([-1, 2]),
+            ICoordinate::new([-1, 3]), ICoordinate::new([-2, 4]), ICoordinate::new([-2, 5]),
+        ]);
+        assert_eq!(start.line_to(&start).collect_vec(), [start]);
+    }
+
+    #[test]
+    fn test_lattice_points_to() {
+        let start = ICoordinate::new([0, 0]);
+        assert_eq!(start.lattice_points_to(&ICoordinate::new([4, -6])).collect_vec(), [
+            ICoordinate::new([0, 0]), ICoordinate::new([2, -3]), ICoordinate::new([4, -6]),
+        ]);
+        assert_eq!(start.lattice_points_to(&ICoordinate::new([1, 2])).count(), 2);
+        assert_eq!(start.lattice_points_to(&start).collect_vec(), [start]);
+    }
+}
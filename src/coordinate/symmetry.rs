@@ -0,0 +1,168 @@
+use std::cmp::Ordering;
+use super::Direction;
+use super::grid::{Grid, Point};
+
+/// One of the eight symmetries of a square: an optional `transpose` followed by some number of
+/// clockwise quarter turns. The same element acts on directions, on points within a grid of a
+/// given shape, and on whole grids, so that `g.apply_grid(grid)[g.apply_point(p, shape)]` is
+/// `grid[p]`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash, Default)]
+pub struct D4 {
+    quarter_turns: u8,
+    transposed: bool,
+}
+
+impl D4 {
+    pub const IDENTITY: D4 = D4::rotation(0);
+    pub const ROTATE_CLOCKWISE: D4 = D4::rotation(1);
+    pub const ROTATE_HALF: D4 = D4::rotation(2);
+    pub const ROTATE_COUNTER_CLOCKWISE: D4 = D4::rotation(3);
+    pub const TRANSPOSE: D4 = D4 { quarter_turns: 0, transposed: true };
+    pub const REFLECT_OVER_COL: D4 = D4 { quarter_turns: 1, transposed: true };
+    pub const TRANSPOSE_SECONDARY: D4 = D4 { quarter_turns: 2, transposed: true };
+    pub const REFLECT_OVER_ROW: D4 = D4 { quarter_turns: 3, transposed: true };
+
+    pub const ALL: [D4; 8] = [
+        D4::IDENTITY, D4::ROTATE_CLOCKWISE, D4::ROTATE_HALF, D4::ROTATE_COUNTER_CLOCKWISE,
+        D4::TRANSPOSE, D4::REFLECT_OVER_COL, D4::TRANSPOSE_SECONDARY, D4::REFLECT_OVER_ROW,
+    ];
+
+    /// Clockwise quarter turns, taken modulo 4
+    pub const fn rotation(quarter_turns: usize) -> Self {
+        Self { quarter_turns: (quarter_turns % 4) as u8, transposed: false }
+    }
+
+    pub const fn quarter_turns(self) -> usize {
+        self.quarter_turns as usize
+    }
+
+    pub const fn is_reflection(self) -> bool {
+        self.transposed
+    }
+
+    /// Apply `self`, then `next`
+    pub const fn then(self, next: Self) -> Self {
+        // Moving a rotation past a transpose reverses its direction
+        let own_turns = if next.transposed { 4 - self.quarter_turns } else { self.quarter_turns };
+        Self {
+            quarter_turns: (next.quarter_turns + own_turns) % 4,
+            transposed: self.transposed != next.transposed,
+        }
+    }
+
+    pub const fn inverse(self) -> Self {
+        if self.transposed {
+            self
+        } else {
+            Self::rotation(4 - self.quarter_turns as usize)
+        }
+    }
+
+    pub const fn apply_direction(self, direction: Direction) -> Direction {
+        let mut result = if self.transposed { direction.transpose() } else { direction };
+        let mut turns = self.quarter_turns;
+        while turns > 0 {
+            result = result.rotate_clockwise();
+            turns -= 1;
+        }
+        result
+    }
+
+    /// Shape of a `(rows, cols)` grid after the transform
+    pub const fn apply_shape(self, (rows, cols): (usize, usize)) -> (usize, usize) {
+        if self.transposed != (self.quarter_turns % 2 == 1) {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        }
+    }
+
+    /// Where `point` ends up when a grid of shape `(rows, cols)` is transformed
+    pub fn apply_point(self, point: Point, shape: (usize, usize)) -> Point {
+        let (mut rows, mut cols) = shape;
+        let [mut r, mut c] = *point;
+        if self.transposed {
+            (r, c) = (c, r);
+            (rows, cols) = (cols, rows);
+        }
+        for _ in 0..self.quarter_turns {
+            (r, c) = (c, rows - 1 - r);
+            (rows, cols) = (cols, rows);
+        }
+        (r, c).into()
+    }
+
+    pub fn apply_grid<T>(self, grid: Grid<T>) -> Grid<T> {
+        let mut result = if self.transposed { grid.transpose() } else { grid };
+        for _ in 0..self.quarter_turns {
+            result = result.rotate_clockwise();
+        }
+        result
+    }
+
+    /// The smallest of the eight images of `grid` (by shape, then row by row), along with the
+    /// transform that produces it
+    pub fn normalize<T: Ord + Clone>(grid: &Grid<T>) -> (Grid<T>, D4) {
+        D4::ALL.iter()
+            .map(|g| (g.apply_grid(grid.clone()), *g))
+            .min_by(|(a, _), (b, _)| compare_grids(a, b))
+            .expect("D4 is not empty")
+    }
+}
+
+fn compare_grids<T: Ord>(a: &Grid<T>, b: &Grid<T>) -> Ordering {
+    a.shape().cmp(&b.shape())
+        .then_with(|| a.iter_rows().cmp(b.iter_rows()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn simple_grid() -> Grid<i32> {
+        Grid::try_from_vec_of_vecs(vec![
+            vec![1, 2, 3],
+            vec![4, 5, 6],
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_group_laws() {
+        for a in D4::ALL {
+            assert_eq!(a.then(a.inverse()), D4::IDENTITY);
+            for b in D4::ALL {
+                for d in Direction::ALL {
+                    assert_eq!(
+                        a.then(b).apply_direction(d),
+                        b.apply_direction(a.apply_direction(d)),
+                    );
+                }
+                assert_eq!(
+                    a.then(b).apply_grid(simple_grid()),
+                    b.apply_grid(a.apply_grid(simple_grid())),
+                );
+            }
+        }
+        assert_eq!(D4::REFLECT_OVER_ROW.apply_direction(Direction::North), Direction::South);
+        assert_eq!(D4::TRANSPOSE_SECONDARY.apply_direction(Direction::North), Direction::East);
+    }
+
+    #[test]
+    fn test_consistent_actions() {
+        let grid = simple_grid();
+        for g in D4::ALL {
+            let transformed = g.apply_grid(grid.clone());
+            assert_eq!(transformed.shape(), g.apply_shape(grid.shape()));
+            for p in grid.iter_idxs() {
+                assert_eq!(transformed[g.apply_point(p, grid.shape())], grid[p]);
+                for d in Direction::ALL {
+                    let Some(moved) = grid.checked_add(&p, &d) else { continue };
+                    assert_eq!(
+                        g.apply_point(moved, grid.shape()),
+                        g.apply_point(p, grid.shape()) + g.apply_direction(d),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,55 +1,502 @@
+pub mod dot;
+pub mod flow;
+pub mod longest_path;
+pub mod min_cut;
+pub mod scc;
+pub mod search;
+pub mod topological;
+pub mod union_find;
+pub mod walk;
+
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::ops::{Add, Range};
+use crate::coordinate::Direction;
+use crate::coordinate::grid::{Grid, Point};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct CycleInfo<T> {
-    dist_to_cycle_start: usize,
+    prefix: Vec<T>,
     cycle: Vec<T>,
 }
 
 impl<T> CycleInfo<T> {
     pub fn dist_to_cycle_start(&self) -> usize {
-        self.dist_to_cycle_start
+        self.prefix.len()
+    }
+
+    /// The states before the cycle is entered
+    pub fn prefix(&self) -> &[T] {
+        &self.prefix
     }
 
     pub fn cycle(&self) -> &[T] {
         &self.cycle
     }
+
+    /// The state at index `n` of the sequence, however far along
+    pub fn nth(&self, n: usize) -> &T {
+        match n.checked_sub(self.prefix.len()) {
+            None => &self.prefix[n],
+            Some(into_cycle) => &self.cycle[into_cycle % self.cycle.len()],
+        }
+    }
+
+    /// Total of `f` over the states at indices `0..n`, adding up each part of the cycle once and
+    /// then scaling it by doubling
+    pub fn sum_over<S: Clone + Add<Output=S> + Default>(&self, n: usize, f: impl Fn(&T) -> S) -> S {
+        let sum = |states: &[T]| states.iter().fold(S::default(), |acc, state| acc + f(state));
+        let Some(into_cycle) = n.checked_sub(self.prefix.len()) else {
+            return sum(&self.prefix[..n]);
+        };
+        let full_cycles = into_cycle / self.cycle.len();
+        let remainder = into_cycle % self.cycle.len();
+        sum(&self.prefix) + repeat_add(sum(&self.cycle), full_cycles) + sum(&self.cycle[..remainder])
+    }
+}
+
+/// `value` added to itself `times` times, in O(log(times)) additions
+fn repeat_add<S: Clone + Add<Output=S> + Default>(mut value: S, mut times: usize) -> S {
+    let mut total = S::default();
+    while times > 0 {
+        if times & 1 == 1 {
+            total = total + value.clone();
+        }
+        value = value.clone() + value;
+        times >>= 1;
+    }
+    total
 }
 
 impl<T: Hash + Eq + Clone> CycleInfo<T> {
     pub fn check_cycle(it: impl IntoIterator<Item=T>) -> Result<Self, Vec<T>> {
+        Self::check_cycle_by_key(it, T::clone)
+    }
+}
+
+impl<T> CycleInfo<T> {
+    /// Like `check_cycle`, but states count as repeated when `key` gives the same result for
+    /// both, so only the keys need to be hashable. Every state is still kept; for states too big
+    /// for that, `CycleShape::check_cycle_by_key` keeps only keys and
+    /// `CycleShape::check_cycle_keeping_lap` only the cycle's states.
+    pub fn check_cycle_by_key<K: Hash + Eq>(
+        it: impl IntoIterator<Item=T>,
+        mut key: impl FnMut(&T) -> K,
+    ) -> Result<Self, Vec<T>> {
         let mut seen = HashMap::new();
         let mut path = vec![];
+        for state in it {
+            match seen.entry(key(&state)) {
+                Entry::Occupied(inner) => {
+                    let cycle = path.split_off(*inner.get());
+                    return Ok(CycleInfo { prefix: path, cycle });
+                },
+                Entry::Vacant(inner) => {
+                    inner.insert(path.len());
+                    path.push(state);
+                },
+            }
+        }
+        Err(path)
+    }
+}
+
+/// 128-bit hash for telling huge states apart without keeping them, using a fixed FNV-1a rather
+/// than `DefaultHasher`, whose output may change between Rust releases. `Hash` impls themselves
+/// aren't promised to stay the same either, so don't persist fingerprints. A collision is
+/// vanishingly unlikely but not impossible.
+pub fn fingerprint<T: Hash + ?Sized>(value: &T) -> u128 {
+    let mut hasher = Fnv128(0x6c62272e07bb014262b821756295c58d);
+    value.hash(&mut hasher);
+    hasher.0
+}
+
+struct Fnv128(u128);
+
+impl Hasher for Fnv128 {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u128).wrapping_mul(0x0000000001000000000000000000013b);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+/// Where a sequence `x, f(x), f(f(x)), ...` starts repeating, found without storing the states.
+/// Both searches run forever if the sequence never repeats.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct CycleShape {
+    dist_to_cycle_start: usize,
+    cycle_len: usize,
+}
+
+impl CycleShape {
+    /// Brent's algorithm: usually fewer calls to `step` than Floyd's
+    pub fn brent<T: Eq + Clone>(start: T, mut step: impl FnMut(&T) -> T) -> Self {
+        let mut power = 1usize;
+        let mut cycle_len = 1usize;
+        let mut tortoise = start.clone();
+        let mut hare = step(&start);
+        while tortoise != hare {
+            if power == cycle_len {
+                tortoise = hare.clone();
+                power *= 2;
+                cycle_len = 0;
+            }
+            hare = step(&hare);
+            cycle_len += 1;
+        }
+
+        // A hare one cycle ahead of the tortoise meets it at the cycle start
+        let mut tortoise = start.clone();
+        let mut hare = start;
+        for _ in 0..cycle_len {
+            hare = step(&hare);
+        }
         let mut dist_to_cycle_start = 0usize;
-        let mut found_cycle = false;
-
-        it.into_iter()
-            .enumerate()
-            .take_while(|(idx, t)| {
-                let t: T = t.clone();
-                let entry = seen.entry(t);
-                match entry {
-                    Entry::Occupied(inner) => {
-                        dist_to_cycle_start = *inner.get();
-                        path.drain(0..dist_to_cycle_start);
-                        found_cycle = true;
-                        false
-                    },
-                    Entry::Vacant(inner) => {
-                        path.push(inner.key().clone());
-                        inner.insert(*idx);
-                        true
-                    },
-                }
-            }).for_each(|_| {});
-        if found_cycle {
-            Ok(CycleInfo { dist_to_cycle_start, cycle: path })
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            dist_to_cycle_start += 1;
+        }
+        Self { dist_to_cycle_start, cycle_len }
+    }
+
+    /// Floyd's tortoise and hare
+    pub fn floyd<T: Eq + Clone>(start: T, mut step: impl FnMut(&T) -> T) -> Self {
+        let mut tortoise = step(&start);
+        let mut hare = step(&tortoise);
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            let halfway = step(&hare);
+            hare = step(&halfway);
+        }
+
+        let mut tortoise = start;
+        let mut dist_to_cycle_start = 0usize;
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            dist_to_cycle_start += 1;
+        }
+
+        let mut cycle_len = 1usize;
+        let mut hare = step(&tortoise);
+        while tortoise != hare {
+            hare = step(&hare);
+            cycle_len += 1;
+        }
+        Self { dist_to_cycle_start, cycle_len }
+    }
+
+    /// Detect a repeat in any sequence by remembering only `key` of each state. Err with the
+    /// number of states seen if the iterator ends first.
+    pub fn check_cycle_by_key<T, K: Hash + Eq>(
+        it: impl IntoIterator<Item=T>,
+        key: impl FnMut(&T) -> K,
+    ) -> Result<Self, usize> {
+        Self::find_repeat(&mut it.into_iter(), key).map(|(shape, _)| shape)
+    }
+
+    /// Like `check_cycle_by_key`, but also returns the states of one lap of the cycle. They are
+    /// read from the iterator after the repeat is found, so nothing before the cycle is kept.
+    pub fn check_cycle_keeping_lap<T, K: Hash + Eq>(
+        it: impl IntoIterator<Item=T>,
+        key: impl FnMut(&T) -> K,
+    ) -> Result<(Self, Vec<T>), usize> {
+        let mut it = it.into_iter();
+        let (shape, repeated) = Self::find_repeat(&mut it, key)?;
+        let mut lap = vec![repeated];
+        lap.extend(it.take(shape.cycle_len - 1));
+        if lap.len() < shape.cycle_len {
+            return Err(shape.dist_to_cycle_start + shape.cycle_len + lap.len());
+        }
+        Ok((shape, lap))
+    }
+
+    /// The shape and the first repeated state
+    fn find_repeat<T, K: Hash + Eq>(
+        it: &mut impl Iterator<Item=T>,
+        mut key: impl FnMut(&T) -> K,
+    ) -> Result<(Self, T), usize> {
+        let mut seen = HashMap::new();
+        for (idx, state) in it.enumerate() {
+            match seen.entry(key(&state)) {
+                Entry::Occupied(inner) => {
+                    let shape = Self { dist_to_cycle_start: *inner.get(), cycle_len: idx - inner.get() };
+                    return Ok((shape, state));
+                },
+                Entry::Vacant(inner) => {
+                    inner.insert(idx);
+                },
+            }
+        }
+        Err(seen.len())
+    }
+
+    pub fn dist_to_cycle_start(&self) -> usize {
+        self.dist_to_cycle_start
+    }
+
+    pub fn cycle_len(&self) -> usize {
+        self.cycle_len
+    }
+
+    /// The smallest index whose state is the same as the state at `n`
+    pub fn reduce_index(&self, n: usize) -> usize {
+        if n < self.dist_to_cycle_start {
+            n
         } else {
-            Err(path)
+            self.dist_to_cycle_start + (n - self.dist_to_cycle_start) % self.cycle_len
         }
     }
+
+    /// The state at index `n`, reached by stepping from `start` no further than the end of the
+    /// first cycle
+    pub fn replay_nth<T>(&self, start: T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+        (0..self.reduce_index(n)).fold(start, |state, _| step(&state))
+    }
+
+    /// Record the states up to the end of the first cycle, which are all `CycleInfo` needs
+    pub fn replay<T>(&self, start: T, mut step: impl FnMut(&T) -> T) -> CycleInfo<T> {
+        let mut states: Vec<_> = iter::successors(Some(start), |state| Some(step(state)))
+            .take(self.dist_to_cycle_start + self.cycle_len)
+            .collect();
+        let cycle = states.split_off(self.dist_to_cycle_start);
+        CycleInfo { prefix: states, cycle }
+    }
+}
+
+pub type NodeId = usize;
+pub type EdgeId = usize;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Edge<E> {
+    pub from: NodeId,
+    pub to: NodeId,
+    pub weight: E,
+}
+
+impl<E> Edge<E> {
+    /// The endpoint that isn't `node`, or `node` itself for a loop
+    pub fn other(&self, node: NodeId) -> NodeId {
+        if self.from == node { self.to } else { self.from }
+    }
+}
+
+/// Nodes are numbered densely in insertion order and carry a unique label. In an undirected graph
+/// every edge is both outgoing and incoming at each of its endpoints.
+#[derive(Clone, Debug)]
+pub struct Graph<N, E> {
+    directed: bool,
+    labels: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    edges: Vec<Edge<E>>,
+    outgoing: Vec<Vec<EdgeId>>,
+    incoming: Vec<Vec<EdgeId>>,
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn new_directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn new_undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self { directed, labels: vec![], ids: HashMap::new(), edges: vec![], outgoing: vec![], incoming: vec![] }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn nodes(&self) -> Range<NodeId> {
+        0..self.node_count()
+    }
+
+    pub fn label(&self, node: NodeId) -> &N {
+        &self.labels[node]
+    }
+
+    pub fn labels(&self) -> &[N] {
+        &self.labels
+    }
+
+    pub fn node_id(&self, label: &N) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+
+    /// Returns the existing id if the label is already present
+    pub fn add_node(&mut self, label: N) -> NodeId {
+        match self.ids.entry(label) {
+            Entry::Occupied(inner) => *inner.get(),
+            Entry::Vacant(inner) => {
+                let id = self.labels.len();
+                self.labels.push(inner.key().clone());
+                self.outgoing.push(vec![]);
+                self.incoming.push(vec![]);
+                inner.insert(id);
+                id
+            },
+        }
+    }
+
+    /// Adds either endpoint if missing. Parallel edges are kept.
+    pub fn add_edge(&mut self, from: N, to: N, weight: E) -> EdgeId {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.add_edge_between(from, to, weight)
+    }
+
+    pub fn add_edge_between(&mut self, from: NodeId, to: NodeId, weight: E) -> EdgeId {
+        assert!(from < self.node_count() && to < self.node_count(), "unknown node");
+        let id = self.edges.len();
+        self.edges.push(Edge { from, to, weight });
+        self.outgoing[from].push(id);
+        if self.directed {
+            self.incoming[to].push(id);
+        } else if from != to {
+            self.outgoing[to].push(id);
+        }
+        id
+    }
+
+    pub fn edge(&self, edge: EdgeId) -> &Edge<E> {
+        &self.edges[edge]
+    }
+
+    pub fn edges(&self) -> &[Edge<E>] {
+        &self.edges
+    }
+
+    /// Each edge leaving `node` along with the node it leads to
+    pub fn out_edges(&self, node: NodeId) -> impl Iterator<Item=(EdgeId, NodeId)> + '_ {
+        self.outgoing[node].iter().map(move |&e| (e, self.edges[e].other(node)))
+    }
+
+    /// Each edge arriving at `node` along with the node it comes from
+    pub fn in_edges(&self, node: NodeId) -> impl Iterator<Item=(EdgeId, NodeId)> + '_ {
+        let ids = if self.directed { &self.incoming[node] } else { &self.outgoing[node] };
+        ids.iter().map(move |&e| (e, self.edges[e].other(node)))
+    }
+
+    pub fn successors(&self, node: NodeId) -> impl Iterator<Item=NodeId> + '_ {
+        self.out_edges(node).map(|(_, next)| next)
+    }
+
+    pub fn predecessors(&self, node: NodeId) -> impl Iterator<Item=NodeId> + '_ {
+        self.in_edges(node).map(|(_, prev)| prev)
+    }
+
+    pub fn out_degree(&self, node: NodeId) -> usize {
+        self.outgoing[node].len()
+    }
+
+    pub fn in_degree(&self, node: NodeId) -> usize {
+        self.in_edges(node).count()
+    }
+}
+
+impl<N: Hash + Eq + Clone, E: Clone> Graph<N, E> {
+    /// Every edge flipped; node and edge ids are unchanged
+    pub fn reverse(&self) -> Self {
+        let mut result = self.clone();
+        if self.directed {
+            for edge in result.edges.iter_mut() {
+                (edge.from, edge.to) = (edge.to, edge.from);
+            }
+            std::mem::swap(&mut result.outgoing, &mut result.incoming);
+        }
+        result
+    }
+
+    /// The induced subgraph on `nodes`, renumbered in the order given
+    pub fn subgraph(&self, nodes: impl IntoIterator<Item=NodeId>) -> Self {
+        let mut result = Self::new(self.directed);
+        let mut new_ids = vec![None; self.node_count()];
+        for node in nodes {
+            new_ids[node] = Some(result.add_node(self.labels[node].clone()));
+        }
+        for edge in self.edges.iter() {
+            if let (Some(from), Some(to)) = (new_ids[edge.from], new_ids[edge.to]) {
+                result.add_edge_between(from, to, edge.weight.clone());
+            }
+        }
+        result
+    }
+}
+
+impl<E> Graph<Point, E> {
+    /// Directed graph of grid cells, with an edge to each orthogonal neighbor for which `edge`
+    /// returns a weight
+    pub fn from_grid<T>(
+        grid: &Grid<T>,
+        mut edge: impl FnMut((Point, &T), Direction, (Point, &T)) -> Option<E>,
+    ) -> Self {
+        let mut result = Self::new_directed();
+        for point in grid.iter_idxs() {
+            result.add_node(point);
+        }
+        for point in grid.iter_idxs() {
+            for (direction, neighbor) in grid.neighbors(&point) {
+                if let Some(weight) = edge((point, &grid[point]), direction, (neighbor, &grid[neighbor])) {
+                    let (from, to) = (result.ids[&point], result.ids[&neighbor]);
+                    result.add_edge_between(from, to, weight);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Graph<Point, usize> {
+    /// Undirected graph of the path cells that are not in the middle of a corridor (forks and dead
+    /// ends), joined by the length of each corridor between them
+    pub fn junctions_from_grid<T>(grid: &Grid<T>, mut is_path: impl FnMut(&Point, &T) -> bool) -> Self {
+        let mut result = Self::new_undirected();
+        let mut exits = vec![];
+        for point in grid.iter_idxs() {
+            if !is_path(&point, &grid[point]) {
+                continue;
+            }
+            let directions: Vec<_> = grid.neighbors(&point)
+                .filter(|(_, neighbor)| is_path(neighbor, &grid[*neighbor]))
+                .map(|(direction, _)| direction)
+                .collect();
+            if directions.len() != 2 {
+                result.add_node(point);
+                exits.push((point, directions));
+            }
+        }
+        for (start, directions) in exits {
+            for direction in directions {
+                let first = grid.checked_add(&start, &direction).expect("neighbor is in bounds");
+                let (end, end_direction, length) = grid.follow_path(&first, direction, &mut is_path);
+                // Each corridor is found once from either end
+                let back = end_direction.opposite();
+                if (start, direction as usize) <= (end, back as usize) {
+                    result.add_edge(start, end, length + 1);
+                }
+            }
+        }
+        result
+    }
 }
 
 pub fn taxicab_dist(c1: (isize, isize), c2: (isize, isize)) -> usize {
@@ -72,4 +519,95 @@ mod test {
         assert_eq!(cycle.dist_to_cycle_start(), 2);
         assert_eq!(cycle.cycle(), [2, 3, 4]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_graph_structure() {
+        let mut graph = Graph::new_directed();
+        graph.add_edge("x", "y", ());
+        graph.add_edge("x", "z", ());
+        graph.add_edge("z", "y", ());
+        let [x, y, z] = ["x", "y", "z"].map(|l| graph.node_id(&l).unwrap());
+        assert_eq!((graph.out_degree(x), graph.in_degree(y)), (2, 2));
+        let reversed = graph.reverse();
+        assert_eq!(reversed.successors(y).collect::<Vec<_>>(), [x, z]);
+        let sub = graph.subgraph([z, y]);
+        assert_eq!(sub.labels(), ["z", "y"]);
+        assert_eq!(sub.edge_count(), 1);
+
+        let mut undirected = Graph::new_undirected();
+        undirected.add_edge(1, 2, ());
+        assert_eq!(undirected.successors(1).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn test_junctions_from_grid() {
+        let grid = Grid::try_from_vec_of_vecs("\
+#.###
+#...#
+#.#.#
+#...#
+###.#".lines().map(|l| l.as_bytes().to_vec()).collect()).unwrap();
+        let graph = Graph::junctions_from_grid(&grid, |_, b| *b != b'#');
+        let start = graph.node_id(&Point::new([0, 1])).unwrap();
+        let fork = graph.node_id(&Point::new([1, 1])).unwrap();
+        let end = graph.node_id(&Point::new([4, 3])).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        // Two corridors of length 4 around the block, plus the two ends
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.dijkstra(start).dist(end), Some(1 + 4 + 1));
+        assert_eq!(graph.out_degree(fork), 3);
+
+        let cells = Graph::from_grid(&grid, |(_, a), _, (_, b)| (*a != b'#' && *b != b'#').then_some(1usize));
+        assert_eq!(cells.dijkstra(cells.node_id(&Point::new([0, 1])).unwrap())
+                       .dist(cells.node_id(&Point::new([4, 3])).unwrap()), Some(6));
+    }
+
+    #[test]
+    fn test_brent_and_floyd() {
+        // Squaring mod 1000 from 2 gives 4, 16, 256, 536, ... and is back at 16 after 20 steps
+        let step = |x: &u64| x * x % 1000;
+        let expected = CycleInfo::check_cycle(iter::successors(Some(2u64), |x| Some(step(x))))
+            .expect("finite state space");
+        for shape in [CycleShape::brent(2, step), CycleShape::floyd(2, step)] {
+            assert_eq!(shape.dist_to_cycle_start(), expected.dist_to_cycle_start());
+            assert_eq!(shape.cycle_len(), expected.cycle().len());
+            assert_eq!(shape.replay(2, step), expected);
+        }
+        let shape = CycleShape::brent(2, step);
+        let far = 1_000_000_007;
+        assert_eq!(shape.replay_nth(2, step, far), expected.cycle()[(far - shape.dist_to_cycle_start()) % shape.cycle_len()]);
+        assert_eq!(CycleShape::floyd(0, step), CycleShape { dist_to_cycle_start: 0, cycle_len: 1 });
+    }
+
+    #[test]
+    fn test_nth_and_sum_over() {
+        let cycle = CycleInfo::check_cycle([5, 7, 1, 2, 3, 1]).unwrap();
+        assert_eq!(cycle.prefix(), [5, 7]);
+        assert_eq!((0..12).map(|n| *cycle.nth(n)).collect::<Vec<_>>(), [5, 7, 1, 2, 3, 1, 2, 3, 1, 2, 3, 1]);
+        for n in 0..12 {
+            assert_eq!(cycle.sum_over(n, |x| *x), (0..n).map(|i| cycle.nth(i)).sum::<i32>());
+        }
+        assert_eq!(cycle.sum_over(2 + 3 * 1_000_000_000 + 1, |x| *x as u64), 12 + 6_000_000_000 + 1);
+    }
+
+    #[test]
+    fn test_check_cycle_by_key() {
+        // Only the remainder matters for where the sequence goes next
+        let states = [(10, "a"), (3, "b"), (7, "c"), (13, "d"), (3, "e")];
+        let cycle = CycleInfo::check_cycle_by_key(states, |(n, _)| n % 10).unwrap();
+        assert_eq!(cycle.prefix(), [(10, "a")]);
+        assert_eq!(cycle.cycle(), [(3, "b"), (7, "c")]);
+
+        let shape = CycleShape::check_cycle_by_key(states, |(n, _)| fingerprint(&(n % 10))).unwrap();
+        assert_eq!((shape.dist_to_cycle_start(), shape.cycle_len()), (1, 2));
+        assert_eq!(CycleShape::check_cycle_by_key(0..5, fingerprint), Err(5));
+        assert_ne!(fingerprint("abc"), fingerprint("abd"));
+        // Hashing nothing leaves FNV-1a's offset basis
+        assert_eq!(fingerprint(&()), 0x6c62272e07bb014262b821756295c58d);
+
+        let (shape, lap) = CycleShape::check_cycle_keeping_lap(states, |(n, _)| n % 10).unwrap();
+        assert_eq!(shape.cycle_len(), 2);
+        // The lap is read after the repeat, so the states are later ones with the same keys
+        assert_eq!(lap, [(13, "d"), (3, "e")]);
+    }
+}
@@ -0,0 +1,64 @@
+use std::hash::Hash;
+use std::io;
+use std::io::Write;
+use super::{Edge, EdgeId, Graph, NodeId};
+
+/// Graphviz attributes such as `("label", ...)` or `("shape", ...)`; values are quoted on output
+pub type DotAttrs = Vec<(&'static str, String)>;
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// Writes the graph in Graphviz DOT format. Nodes are named `n<id>`, which is what Graphviz
+    /// shows for nodes given no `label` attribute.
+    pub fn write_dot(
+        &self,
+        out: &mut impl Write,
+        mut node_attrs: impl FnMut(NodeId, &N) -> DotAttrs,
+        mut edge_attrs: impl FnMut(EdgeId, &Edge<E>) -> DotAttrs,
+    ) -> io::Result<()> {
+        let (kind, arrow) = if self.is_directed() { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(out, "{kind} {{")?;
+        for node in self.nodes() {
+            writeln!(out, "    n{node}{};", format_attrs(&node_attrs(node, self.label(node))))?;
+        }
+        for (id, edge) in self.edges().iter().enumerate() {
+            writeln!(out, "    n{} {arrow} n{}{};", edge.from, edge.to, format_attrs(&edge_attrs(id, edge)))?;
+        }
+        writeln!(out, "}}")
+    }
+}
+
+fn format_attrs(attrs: &[(&str, String)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let attrs: Vec<_> = attrs.iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!(" [{}]", attrs.join(", "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_dot() {
+        let mut graph = Graph::new_undirected();
+        graph.add_edge("a", "b \"quoted\"", 3);
+        graph.add_node("c");
+        let mut out = vec![];
+        graph.write_dot(
+            &mut out,
+            |node, label| if node == 2 { vec![] } else { vec![("label", label.to_string()), ("shape", "box".into())] },
+            |_, edge| vec![("label", edge.weight.to_string())],
+        ).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+graph {
+    n0 [label=\"a\", shape=\"box\"];
+    n1 [label=\"b \\\"quoted\\\"\", shape=\"box\"];
+    n2;
+    n0 -- n1 [label=\"3\"];
+}
+");
+    }
+}
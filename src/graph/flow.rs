@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::ops::{Add, Sub};
+use super::{EdgeId, Graph, NodeId};
+use super::min_cut::Cut;
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct MaxFlow<W> {
+    pub value: W,
+    /// Indexed by edge: the direction the flow runs and how much. In a directed graph this is
+    /// always along the edge; in an undirected one it may be either way.
+    pub flows: Vec<(NodeId, NodeId, W)>,
+    /// Minimum cut with the source's side first; its edges are the saturated ones leaving it
+    pub cut: Cut<W>,
+}
+
+/// Residual network: arc `2 * e` runs along edge `e` and arc `2 * e + 1` against it
+struct Dinic<W> {
+    arcs: Vec<(NodeId, W)>,
+    adjacent: Vec<Vec<usize>>,
+    level: Vec<Option<usize>>,
+    next_arc: Vec<usize>,
+}
+
+impl<N: Hash + Eq + Clone, E: Copy + Ord + Add<Output=E> + Sub<Output=E> + Default> Graph<N, E> {
+    /// Maximum flow from `source` to `sink` with edge weights as capacities, by Dinic's algorithm.
+    /// Undirected edges carry up to their capacity in either direction.
+    pub fn max_flow(&self, source: NodeId, sink: NodeId) -> MaxFlow<E> {
+        self.max_flow_by(source, sink, |edge| self.edge(edge).weight)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// Maximum flow with every edge given capacity one, which counts edge-disjoint paths
+    pub fn unit_max_flow(&self, source: NodeId, sink: NodeId) -> MaxFlow<usize> {
+        self.max_flow_by(source, sink, |_| 1)
+    }
+
+    fn max_flow_by<W: Copy + Ord + Add<Output=W> + Sub<Output=W> + Default>(
+        &self,
+        source: NodeId,
+        sink: NodeId,
+        capacity: impl Fn(EdgeId) -> W,
+    ) -> MaxFlow<W> {
+        assert_ne!(source, sink, "source and sink must differ");
+        let zero = W::default();
+        let mut dinic = Dinic {
+            arcs: Vec::with_capacity(2 * self.edge_count()),
+            adjacent: vec![vec![]; self.node_count()],
+            level: vec![],
+            next_arc: vec![],
+        };
+        for (id, edge) in self.edges().iter().enumerate() {
+            let cap = capacity(id);
+            dinic.arcs.push((edge.to, cap));
+            dinic.arcs.push((edge.from, if self.is_directed() { zero } else { cap }));
+            dinic.adjacent[edge.from].push(2 * id);
+            dinic.adjacent[edge.to].push(2 * id + 1);
+        }
+        let mut value = zero;
+        while dinic.find_levels(source, sink) {
+            dinic.next_arc = vec![0; self.node_count()];
+            loop {
+                let pushed = dinic.augment(source, sink, None);
+                if pushed == zero {
+                    break;
+                }
+                value = value + pushed;
+            }
+        }
+
+        let flows = self.edges().iter().enumerate()
+            .map(|(id, edge)| {
+                let (cap, residual) = (capacity(id), dinic.arcs[2 * id].1);
+                // Undirected edges start with `cap` each way, so net flow against the edge leaves
+                // more than `cap` along it
+                if residual <= cap {
+                    (edge.from, edge.to, cap - residual)
+                } else {
+                    (edge.to, edge.from, residual - cap)
+                }
+            })
+            .collect();
+        let in_first: Vec<_> = dinic.level.iter().map(|level| level.is_some()).collect();
+        let edges = self.edges().iter().enumerate()
+            .filter(|(_, edge)| {
+                in_first[edge.from] != in_first[edge.to] && (!self.is_directed() || in_first[edge.from])
+            })
+            .map(|(id, _)| id)
+            .collect();
+        let (first, second) = self.nodes().partition(|node| in_first[*node]);
+        MaxFlow { value, flows, cut: Cut { weight: value, edges, sides: [first, second] } }
+    }
+}
+
+impl<W: Copy + Ord + Add<Output=W> + Sub<Output=W> + Default> Dinic<W> {
+    /// Breadth-first levels over arcs with capacity left; false once the sink is out of reach
+    fn find_levels(&mut self, source: NodeId, sink: NodeId) -> bool {
+        self.level = vec![None; self.adjacent.len()];
+        self.level[source] = Some(0);
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for arc in self.adjacent[node].iter() {
+                let (next, residual) = self.arcs[*arc];
+                if residual > W::default() && self.level[next].is_none() {
+                    self.level[next] = self.level[node].map(|level| level + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        self.level[sink].is_some()
+    }
+
+    /// Pushes flow along one path of increasing level, at most `limit` if given
+    fn augment(&mut self, node: NodeId, sink: NodeId, limit: Option<W>) -> W {
+        if node == sink {
+            return limit.expect("the source is not the sink");
+        }
+        while let Some(&arc) = self.adjacent[node].get(self.next_arc[node]) {
+            let (next, residual) = self.arcs[arc];
+            if residual > W::default() && self.level[next] == self.level[node].map(|level| level + 1) {
+                let pushed = self.augment(next, sink, Some(limit.map_or(residual, |limit| limit.min(residual))));
+                if pushed > W::default() {
+                    self.arcs[arc].1 = residual - pushed;
+                    self.arcs[arc ^ 1].1 = self.arcs[arc ^ 1].1 + pushed;
+                    return pushed;
+                }
+            }
+            self.next_arc[node] += 1;
+        }
+        W::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        // The network from CLRS
+        let mut graph = Graph::new_directed();
+        for (from, to, cap) in [
+            ('s', 'a', 16), ('s', 'c', 13), ('a', 'b', 12), ('c', 'a', 4), ('b', 'c', 9),
+            ('c', 'd', 14), ('d', 'b', 7), ('b', 't', 20), ('d', 't', 4),
+        ] {
+            graph.add_edge(from, to, cap);
+        }
+        let [s, t] = ['s', 't'].map(|l| graph.node_id(&l).unwrap());
+        let flow = graph.max_flow(s, t);
+        assert_eq!(flow.value, 23);
+        for node in graph.nodes().filter(|node| ![s, t].contains(node)) {
+            let inflow: u32 = flow.flows.iter().filter(|(_, to, _)| *to == node).map(|(_, _, f)| f).sum();
+            let outflow: u32 = flow.flows.iter().filter(|(from, _, _)| *from == node).map(|(_, _, f)| f).sum();
+            assert_eq!(inflow, outflow);
+        }
+        let cut_capacity: u32 = flow.cut.edges.iter().map(|edge| graph.edge(*edge).weight).sum();
+        assert_eq!(cut_capacity, 23);
+        assert!(flow.cut.sides[0].contains(&s) && flow.cut.sides[1].contains(&t));
+    }
+
+    #[test]
+    fn test_unit_max_flow() {
+        // Two 4-cliques joined by two edges
+        let mut graph = Graph::new_undirected();
+        for node in 0..8 {
+            graph.add_node(node);
+        }
+        for offset in [0, 4] {
+            for (a, b) in [(1, 0), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)] {
+                graph.add_edge(offset + a, offset + b, ());
+            }
+        }
+        graph.add_edge(1, 5, ());
+        graph.add_edge(6, 2, ());
+        let flow = graph.unit_max_flow(0, 4);
+        assert_eq!(flow.value, 2);
+        assert_eq!(flow.cut.edges.len(), 2);
+        assert_eq!(flow.cut.sides, [vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+        // The last edge is stored from the sink's side but its flow runs towards the sink
+        assert_eq!(flow.flows[13], (2, 6, 1));
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::ops::Add;
+use super::{Graph, NodeId};
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct LongestPath<W> {
+    pub length: W,
+    /// From the start to the end, both included
+    pub path: Vec<NodeId>,
+}
+
+struct Search<'a, N, E> {
+    graph: &'a Graph<N, E>,
+    target: NodeId,
+    /// Heaviest edge into each node, so the sum over unvisited nodes bounds what is left to gain
+    max_in: Vec<E>,
+    /// Longest prefix seen so far arriving at a node having visited exactly a set of nodes
+    best_prefix: HashMap<(NodeId, u64), E>,
+    /// New states stop being remembered once the memo holds this many
+    memo_limit: usize,
+    /// States that got past pruning, for measuring it
+    expanded: usize,
+    path: Vec<NodeId>,
+    best: Option<LongestPath<E>>,
+}
+
+/// Default bound on `longest_simple_path`'s memo, about a hundred megabytes at most
+pub const LONGEST_PATH_MEMO_LIMIT: usize = 1 << 22;
+
+impl<N: Hash + Eq + Clone, E: Copy + Ord + Add<Output=E> + Default> Graph<N, E> {
+    /// Longest path from `start` to `end` that repeats no node, found by depth-first search over
+    /// visited-node bitmasks with memoization and branch-and-bound pruning. Only for graphs of at
+    /// most 64 nodes; edge weights must not be negative.
+    pub fn longest_simple_path(&self, start: NodeId, end: NodeId) -> Option<LongestPath<E>> {
+        self.longest_simple_path_with_memo(start, end, LONGEST_PATH_MEMO_LIMIT)
+    }
+
+    /// As `longest_simple_path`, remembering at most `memo_limit` `(node, visited)` states. Once
+    /// the memo is full, only states already in it keep pruning; zero turns it off.
+    pub fn longest_simple_path_with_memo(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        memo_limit: usize,
+    ) -> Option<LongestPath<E>> {
+        self.search_longest(start, end, memo_limit).best
+    }
+
+    fn search_longest(&self, start: NodeId, end: NodeId, memo_limit: usize) -> Search<'_, N, E> {
+        assert!(self.node_count() <= 64, "too many nodes for a u64 mask");
+        let mut max_in = vec![E::default(); self.node_count()];
+        for node in self.nodes() {
+            for (edge, next) in self.out_edges(node) {
+                max_in[next] = max_in[next].max(self.edge(edge).weight);
+            }
+        }
+        let mut search = Search {
+            graph: self,
+            target: end,
+            max_in,
+            best_prefix: HashMap::new(),
+            memo_limit,
+            expanded: 0,
+            path: vec![start],
+            best: None,
+        };
+        search.visit(start, 1 << start, E::default());
+        search
+    }
+}
+
+impl<N: Hash + Eq + Clone, E: Copy + Ord + Add<Output=E> + Default> Search<'_, N, E> {
+    fn visit(&mut self, node: NodeId, visited: u64, length: E) {
+        if node == self.target {
+            if self.best.as_ref().is_none_or(|best| length > best.length) {
+                self.best = Some(LongestPath { length, path: self.path.clone() });
+            }
+            return;
+        }
+        if let Some(best) = &self.best {
+            if length + self.remaining_bound(visited) <= best.length {
+                return;
+            }
+        }
+        let memo_full = self.best_prefix.len() >= self.memo_limit;
+        match self.best_prefix.entry((node, visited)) {
+            Entry::Occupied(mut inner) => {
+                if *inner.get() >= length {
+                    return;
+                }
+                inner.insert(length);
+            },
+            Entry::Vacant(inner) => {
+                if !memo_full {
+                    inner.insert(length);
+                }
+            },
+        }
+        self.expanded += 1;
+        let graph = self.graph;
+        for (edge, next) in graph.out_edges(node) {
+            if visited & (1 << next) != 0 {
+                continue;
+            }
+            self.path.push(next);
+            self.visit(next, visited | (1 << next), length + graph.edge(edge).weight);
+            self.path.pop();
+        }
+    }
+
+    fn remaining_bound(&self, visited: u64) -> E {
+        (0..self.max_in.len())
+            .filter(|node| visited & (1 << node) == 0)
+            .fold(E::default(), |acc, node| acc + self.max_in[node])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_longest_simple_path() {
+        // A square with one diagonal; the longest route from a to c goes the long way round
+        let mut graph = Graph::new_undirected();
+        for (from, to, weight) in [('a', 'b', 3), ('b', 'c', 1), ('c', 'd', 2), ('d', 'a', 6), ('b', 'd', 5)] {
+            graph.add_edge(from, to, weight);
+        }
+        let [a, b, c, d] = ['a', 'b', 'c', 'd'].map(|l| graph.node_id(&l).unwrap());
+        let longest = graph.longest_simple_path(a, c).unwrap();
+        assert_eq!(longest.length, 12);
+        assert_eq!(longest.path, [a, d, b, c]);
+        assert_eq!(graph.longest_simple_path(a, a).unwrap().length, 0);
+
+        let mut directed = Graph::new_directed();
+        directed.add_edge('x', 'y', 1);
+        directed.add_node('z');
+        assert_eq!(directed.longest_simple_path(1, 0), None);
+        assert_eq!(directed.longest_simple_path(0, 2), None);
+    }
+
+    #[test]
+    fn test_memo_prunes_revisits() {
+        // A 4x4 grid between opposite corners has no Hamiltonian path, so the bound never cuts
+        // the search short and only the memo stops it reaching a state by several routes
+        let mut graph = Graph::new_undirected();
+        for row in 0..4 {
+            for col in 0..4 {
+                if row < 3 {
+                    graph.add_edge((row, col), (row + 1, col), 1);
+                }
+                if col < 3 {
+                    graph.add_edge((row, col), (row, col + 1), 1);
+                }
+            }
+        }
+        let [start, end] = [(0, 0), (3, 3)].map(|l| graph.node_id(&l).unwrap());
+        let memoized = graph.search_longest(start, end, LONGEST_PATH_MEMO_LIMIT);
+        let unmemoized = graph.search_longest(start, end, 0);
+        assert_eq!(memoized.best.as_ref().unwrap().length, 14);
+        assert_eq!(unmemoized.best.unwrap().length, 14);
+        assert!(memoized.expanded < unmemoized.expanded);
+        assert!(memoized.best_prefix.len() <= memoized.expanded);
+        let bounded = graph.search_longest(start, end, 10);
+        assert_eq!(bounded.best_prefix.len(), 10);
+        assert_eq!(bounded.best.unwrap().length, 14);
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+use itertools::Itertools;
+use crate::util::XorShift64;
+use super::{EdgeId, Graph, NodeId};
+use super::union_find::UnionFind;
+
+/// A split of the nodes into two non-empty sides and the edges running between them
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Cut<W> {
+    pub weight: W,
+    pub edges: Vec<EdgeId>,
+    pub sides: [Vec<NodeId>; 2],
+}
+
+impl<W> Cut<W> {
+    fn from_membership<N: Hash + Eq + Clone, E>(
+        graph: &Graph<N, E>,
+        in_first: &[bool],
+        weight: impl FnOnce(&[EdgeId]) -> W,
+    ) -> Self {
+        let edges: Vec<_> = graph.edges().iter().enumerate()
+            .filter(|(_, edge)| in_first[edge.from] != in_first[edge.to])
+            .map(|(id, _)| id)
+            .collect();
+        let (first, second) = graph.nodes().partition(|node| in_first[*node]);
+        Self { weight: weight(&edges), edges, sides: [first, second] }
+    }
+}
+
+impl<N: Hash + Eq + Clone, E: Copy + Ord + Add<Output=E> + Default> Graph<N, E> {
+    /// Deterministic global minimum cut of an undirected graph with non-negative weights. None if
+    /// there are fewer than two nodes.
+    pub fn stoer_wagner_min_cut(&self) -> Option<Cut<E>> {
+        assert!(!self.is_directed(), "minimum cut needs an undirected graph");
+        let n = self.node_count();
+        if n < 2 {
+            return None;
+        }
+        // Adjacency between merged nodes; loops are dropped
+        let mut adjacent: Vec<HashMap<NodeId, E>> = vec![HashMap::new(); n];
+        for edge in self.edges().iter().filter(|edge| edge.from != edge.to) {
+            for (a, b) in [(edge.from, edge.to), (edge.to, edge.from)] {
+                let total = adjacent[a].entry(b).or_default();
+                *total = *total + edge.weight;
+            }
+        }
+        let mut merged: Vec<Vec<NodeId>> = self.nodes().map(|node| vec![node]).collect();
+        let mut active: Vec<NodeId> = self.nodes().collect();
+        let mut best: Option<(E, Vec<NodeId>)> = None;
+
+        while active.len() > 1 {
+            // Maximum adjacency order, with stale heap entries skipped
+            let mut connection = vec![E::default(); n];
+            let mut added = vec![false; n];
+            let mut heap = BinaryHeap::from([(E::default(), active[0])]);
+            let (mut previous, mut last) = (active[0], active[0]);
+            let mut remaining = active.len();
+            while remaining > 0 {
+                let node = match heap.pop() {
+                    Some((weight, node)) => {
+                        if added[node] || weight != connection[node] {
+                            continue;
+                        }
+                        node
+                    },
+                    // Disconnected: carry on from any node not yet added
+                    None => *active.iter().find(|node| !added[**node]).expect("remaining > 0"),
+                };
+                added[node] = true;
+                remaining -= 1;
+                (previous, last) = (last, node);
+                for (&next, &w) in adjacent[node].iter() {
+                    if !added[next] {
+                        connection[next] = connection[next] + w;
+                        heap.push((connection[next], next));
+                    }
+                }
+            }
+            let cut_of_phase = connection[last];
+            if best.as_ref().is_none_or(|(weight, _)| cut_of_phase < *weight) {
+                best = Some((cut_of_phase, merged[last].clone()));
+            }
+
+            // Merge the last node into the one before it
+            let last_edges = std::mem::take(&mut adjacent[last]);
+            for (next, w) in last_edges {
+                adjacent[next].remove(&last);
+                if next == previous {
+                    continue;
+                }
+                for (a, b) in [(previous, next), (next, previous)] {
+                    let total = adjacent[a].entry(b).or_default();
+                    *total = *total + w;
+                }
+            }
+            let moved = std::mem::take(&mut merged[last]);
+            merged[previous].extend(moved);
+            active.retain(|node| *node != last);
+        }
+
+        let (weight, side) = best.expect("at least one phase ran");
+        let mut in_first = vec![false; n];
+        for node in side {
+            in_first[node] = true;
+        }
+        Some(Cut::from_membership(self, &in_first, |_| weight))
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// Randomized global minimum cut of an undirected graph, counting every edge as weight one.
+    /// Each trial finds a minimum cut with probability about `1 / log(n)`, so take on the order of
+    /// `log(n)^2` trials for high confidence. None if there are fewer than two nodes.
+    pub fn karger_stein_min_cut(&self, seed: u64, trials: usize) -> Option<Cut<usize>> {
+        assert!(!self.is_directed(), "minimum cut needs an undirected graph");
+        if self.node_count() < 2 {
+            return None;
+        }
+        let mut rng = XorShift64::new(seed);
+        let edges = merge_parallel(self.edges().iter().map(|edge| (edge.from, edge.to, 1)));
+        let (_, in_first) = (0..trials.max(1))
+            .map(|_| karger_stein(self.node_count(), &edges, &mut rng))
+            .min_by_key(|(size, _)| *size)
+            .expect("at least one trial");
+        Some(Cut::from_membership(self, &in_first, |edges| edges.len()))
+    }
+}
+
+/// Edges between contracted nodes, with parallel edges merged into a multiplicity
+type Multigraph = Vec<(usize, usize, usize)>;
+
+fn merge_parallel(edges: impl IntoIterator<Item=(usize, usize, usize)>) -> Multigraph {
+    let mut sorted: Multigraph = edges.into_iter()
+        .filter(|(a, b, _)| a != b)
+        .map(|(a, b, count)| (a.min(b), a.max(b), count))
+        .collect();
+    sorted.sort_unstable();
+    sorted.into_iter()
+        .coalesce(|x, y| if (x.0, x.1) == (y.0, y.1) { Ok((x.0, x.1, x.2 + y.2)) } else { Err((x, y)) })
+        .collect()
+}
+
+/// Size of the best cut found and which side each node is on
+fn karger_stein(n: usize, edges: &Multigraph, rng: &mut XorShift64) -> (usize, Vec<bool>) {
+    if edges.is_empty() {
+        let mut in_first = vec![false; n];
+        in_first[0] = true;
+        return (0, in_first);
+    }
+    if n <= 6 {
+        return brute_force_cut(n, edges);
+    }
+    let target = 1 + (n as f64 / std::f64::consts::SQRT_2).ceil() as usize;
+    (0..2)
+        .map(|_| {
+            let (groups, count, contracted) = contract(n, edges, target, rng);
+            let (size, in_first) = karger_stein(count, &contracted, rng);
+            (size, groups.iter().map(|group| in_first[*group]).collect())
+        })
+        .min_by_key(|(size, _): &(usize, Vec<bool>)| *size)
+        .expect("two attempts")
+}
+
+/// Contract random edges until `target` groups remain, returning each node's group, the number of
+/// groups and the edges left between groups
+fn contract(n: usize, edges: &Multigraph, target: usize, rng: &mut XorShift64) -> (Vec<usize>, usize, Multigraph) {
+    // The first of `count` unit edges to come up in a uniform shuffle is exponentially distributed
+    // with rate `count`, so ordering by these keys picks edges as if they were never merged
+    let mut order: Vec<_> = edges.iter()
+        .map(|&(a, b, count)| (-(1.0 - rng.next_f64()).ln() / count as f64, a, b))
+        .collect();
+    order.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let mut sets = UnionFind::new(n);
+    for (_, a, b) in order {
+        if sets.component_count() <= target {
+            break;
+        }
+        sets.union(a, b);
+    }
+    let mut groups = vec![0; n];
+    let components = sets.components();
+    for (group, members) in components.iter().enumerate() {
+        for node in members.iter() {
+            groups[*node] = group;
+        }
+    }
+    let contracted = merge_parallel(edges.iter().map(|&(a, b, c)| (groups[a], groups[b], c)));
+    (groups, components.len(), contracted)
+}
+
+fn brute_force_cut(n: usize, edges: &Multigraph) -> (usize, Vec<bool>) {
+    // Node n - 1 always stays on the second side, so each split is tried once
+    (1..(1u32 << (n - 1)))
+        .map(|mask| {
+            let size = edges.iter()
+                .filter(|(a, b, _)| (mask >> a) & 1 != (mask >> b) & 1)
+                .map(|(_, _, count)| count)
+                .sum();
+            (size, mask)
+        })
+        .min_by_key(|(size, _)| *size)
+        .map(|(size, mask)| (size, (0..n).map(|node| (mask >> node) & 1 == 1).collect()))
+        .expect("n >= 2")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The example from Stoer and Wagner's paper
+    fn paper_graph() -> Graph<u8, u32> {
+        let mut graph = Graph::new_undirected();
+        for (a, b, w) in [
+            (1, 2, 2), (1, 5, 3), (2, 3, 3), (2, 5, 2), (2, 6, 2), (3, 4, 4),
+            (3, 7, 2), (4, 7, 2), (4, 8, 2), (5, 6, 3), (6, 7, 1), (7, 8, 3),
+        ] {
+            graph.add_edge(a, b, w);
+        }
+        graph
+    }
+
+    /// Two 5-cliques joined by three edges
+    fn barbell() -> Graph<u8, ()> {
+        let mut graph = Graph::new_undirected();
+        for offset in [0, 5] {
+            for a in 0..5 {
+                for b in (a + 1)..5 {
+                    graph.add_edge(offset + a, offset + b, ());
+                }
+            }
+        }
+        for (a, b) in [(0, 5), (1, 6), (2, 7)] {
+            graph.add_edge(a, b, ());
+        }
+        graph
+    }
+
+    fn labelled_sides<N: Hash + Eq + Clone + Ord, W>(graph: &Graph<N, W>, cut: &Cut<impl Sized>) -> Vec<Vec<N>> {
+        let mut sides: Vec<Vec<N>> = cut.sides.iter()
+            .map(|side| {
+                let mut labels: Vec<_> = side.iter().map(|node| graph.label(*node).clone()).collect();
+                labels.sort();
+                labels
+            })
+            .collect();
+        sides.sort();
+        sides
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        let graph = paper_graph();
+        let cut = graph.stoer_wagner_min_cut().unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(labelled_sides(&graph, &cut), [vec![1, 2, 5, 6], vec![3, 4, 7, 8]]);
+        assert_eq!(cut.edges.iter().map(|e| graph.edge(*e).weight).sum::<u32>(), 4);
+
+        let weighted_barbell = {
+            let mut graph = Graph::new_undirected();
+            for edge in barbell().edges() {
+                graph.add_edge(edge.from, edge.to, 1u32);
+            }
+            graph
+        };
+        assert_eq!(weighted_barbell.stoer_wagner_min_cut().unwrap().weight, 3);
+    }
+
+    #[test]
+    fn test_karger_stein() {
+        let graph = barbell();
+        let cut = graph.karger_stein_min_cut(42, 10).unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.edges.len(), 3);
+        assert_eq!(labelled_sides(&graph, &cut), [vec![0, 1, 2, 3, 4], vec![5, 6, 7, 8, 9]]);
+
+        let mut disconnected = Graph::new_undirected();
+        disconnected.add_edge('a', 'b', ());
+        disconnected.add_node('c');
+        assert_eq!(disconnected.karger_stein_min_cut(1, 1).unwrap().weight, 0);
+        assert_eq!(Graph::<char, ()>::new_undirected().karger_stein_min_cut(1, 1), None);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use super::{Graph, NodeId};
+
+/// Strongly connected components, numbered so that edges between components only go from lower
+/// to higher numbers
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Components {
+    component_of: Vec<usize>,
+    members: Vec<Vec<NodeId>>,
+}
+
+impl Components {
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn component_of(&self, node: NodeId) -> usize {
+        self.component_of[node]
+    }
+
+    pub fn members(&self, component: usize) -> &[NodeId] {
+        &self.members[component]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&[NodeId]> + '_ {
+        self.members.iter().map(|members| members.as_slice())
+    }
+}
+
+struct Tarjan {
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<NodeId>,
+    next_index: usize,
+    members: Vec<Vec<NodeId>>,
+}
+
+impl Tarjan {
+    fn enter(&mut self, node: NodeId) {
+        self.index[node] = Some(self.next_index);
+        self.low[node] = self.next_index;
+        self.next_index += 1;
+        self.on_stack[node] = true;
+        self.stack.push(node);
+    }
+
+    /// Once every successor is done, `node` closes a component if nothing below it reached higher
+    fn leave(&mut self, node: NodeId) {
+        if Some(self.low[node]) != self.index[node] {
+            return;
+        }
+        let start = self.stack.iter().rposition(|n| *n == node).expect("node is on the stack");
+        let component = self.stack.split_off(start);
+        for member in component.iter() {
+            self.on_stack[*member] = false;
+        }
+        self.members.push(component);
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// Tarjan's algorithm, without recursion
+    pub fn strongly_connected_components(&self) -> Components {
+        let n = self.node_count();
+        let mut tarjan = Tarjan {
+            index: vec![None; n],
+            low: vec![0; n],
+            on_stack: vec![false; n],
+            stack: vec![],
+            next_index: 0,
+            members: vec![],
+        };
+        for root in self.nodes() {
+            if tarjan.index[root].is_some() {
+                continue;
+            }
+            tarjan.enter(root);
+            let mut calls = vec![(root, self.successors(root))];
+            while let Some((node, successors)) = calls.last_mut() {
+                let node = *node;
+                match successors.next() {
+                    Some(next) => match tarjan.index[next] {
+                        None => {
+                            tarjan.enter(next);
+                            calls.push((next, self.successors(next)));
+                        },
+                        Some(index) if tarjan.on_stack[next] => tarjan.low[node] = tarjan.low[node].min(index),
+                        Some(_) => {},
+                    },
+                    None => {
+                        calls.pop();
+                        if let Some((parent, _)) = calls.last() {
+                            tarjan.low[*parent] = tarjan.low[*parent].min(tarjan.low[node]);
+                        }
+                        tarjan.leave(node);
+                    },
+                }
+            }
+        }
+        // Tarjan finds components in reverse topological order
+        let mut members = tarjan.members;
+        members.reverse();
+        let mut component_of = vec![0; n];
+        for (component, nodes) in members.iter().enumerate() {
+            for node in nodes.iter() {
+                component_of[*node] = component;
+            }
+        }
+        Components { component_of, members }
+    }
+
+    /// The DAG of components, with node ids matching component numbers and one edge for each
+    /// pair of components joined by any edge
+    pub fn condensation(&self, components: &Components) -> Graph<usize, ()> {
+        let mut dag = Graph::new_directed();
+        for component in 0..components.len() {
+            dag.add_node(component);
+        }
+        let mut joined = HashSet::new();
+        for edge in self.edges() {
+            let (from, to) = (components.component_of(edge.from), components.component_of(edge.to));
+            if from != to && joined.insert((from, to)) {
+                dag.add_edge_between(from, to, ());
+            }
+        }
+        dag
+    }
+
+    /// For each predecessor of `node`, the nodes that reach it without passing through `node`,
+    /// itself included. Nodes feeding several predecessors appear in each.
+    pub fn feeding_subgraphs(&self, node: NodeId) -> Vec<(NodeId, Vec<NodeId>)> {
+        let mut inputs: Vec<_> = self.predecessors(node).filter(|prev| *prev != node).collect();
+        inputs.sort_unstable();
+        inputs.dedup();
+        inputs.into_iter()
+            .map(|input| {
+                let mut seen = vec![false; self.node_count()];
+                seen[node] = true;
+                seen[input] = true;
+                let mut stack = vec![input];
+                let mut feeding = vec![];
+                while let Some(current) = stack.pop() {
+                    feeding.push(current);
+                    for prev in self.predecessors(current) {
+                        if !seen[prev] {
+                            seen[prev] = true;
+                            stack.push(prev);
+                        }
+                    }
+                }
+                feeding.sort_unstable();
+                (input, feeding)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_components_and_condensation() {
+        let mut graph = Graph::new_directed();
+        for (from, to) in [('a', 'b'), ('b', 'c'), ('c', 'a'), ('c', 'd'), ('d', 'e'), ('e', 'd'), ('b', 'e'), ('f', 'e')] {
+            graph.add_edge(from, to, ());
+        }
+        let components = graph.strongly_connected_components();
+        let label = |members: &[NodeId]| {
+            let mut labels: Vec<_> = members.iter().map(|node| *graph.label(*node)).collect();
+            labels.sort();
+            labels.into_iter().collect::<String>()
+        };
+        let mut found: Vec<_> = components.iter().map(label).collect();
+        found.sort();
+        assert_eq!(found, ["abc", "de", "f"]);
+
+        let dag = graph.condensation(&components);
+        let [abc, de, f] = ['a', 'd', 'f'].map(|l| components.component_of(graph.node_id(&l).unwrap()));
+        assert_eq!(dag.edge_count(), 2);
+        assert_eq!(dag.successors(abc).collect::<Vec<_>>(), [de]);
+        assert_eq!(dag.successors(f).collect::<Vec<_>>(), [de]);
+        assert!(abc < de && f < de);
+    }
+
+    #[test]
+    fn test_feeding_subgraphs() {
+        // Two counters, each looping on itself, fed by a shared source and joined at a sink
+        let mut graph = Graph::new_directed();
+        for (from, to) in [("src", "a1"), ("a1", "a2"), ("a2", "a1"), ("src", "b1"), ("b1", "b2"), ("b2", "b1"),
+                           ("a2", "sink"), ("b2", "sink"), ("sink", "rx")] {
+            graph.add_edge(from, to, ());
+        }
+        let id = |l: &str| graph.node_id(&l).unwrap();
+        let sink = id("sink");
+        let feeding = graph.feeding_subgraphs(sink);
+        let mut expected = vec![
+            (id("a2"), vec![id("src"), id("a1"), id("a2")]),
+            (id("b2"), vec![id("src"), id("b1"), id("b2")]),
+        ];
+        for (_, nodes) in expected.iter_mut() {
+            nodes.sort();
+        }
+        assert_eq!(feeding, expected);
+        assert_eq!(graph.feeding_subgraphs(id("rx")).len(), 1);
+    }
+}
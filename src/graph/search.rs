@@ -0,0 +1,150 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::iter;
+use std::ops::Add;
+use super::{Graph, NodeId};
+
+/// Distances and predecessors from a single source
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ShortestPaths<W> {
+    source: NodeId,
+    dist: Vec<Option<W>>,
+    prev: Vec<Option<NodeId>>,
+}
+
+impl<W: Copy> ShortestPaths<W> {
+    pub fn source(&self) -> NodeId {
+        self.source
+    }
+
+    /// None if `node` is unreachable
+    pub fn dist(&self, node: NodeId) -> Option<W> {
+        self.dist[node]
+    }
+
+    /// Nodes from the source to `node`, both included
+    pub fn path_to(&self, node: NodeId) -> Option<Vec<NodeId>> {
+        self.dist[node]?;
+        let mut path: Vec<_> = iter::successors(Some(node), |n| self.prev[*n]).collect();
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// Nodes in breadth-first order along with their distance in edges from `start`
+    pub fn bfs(&self, start: NodeId) -> impl Iterator<Item=(NodeId, usize)> + '_ {
+        let mut seen = vec![false; self.node_count()];
+        seen[start] = true;
+        let mut queue = VecDeque::from([(start, 0usize)]);
+        iter::from_fn(move || {
+            let (node, depth) = queue.pop_front()?;
+            for next in self.successors(node) {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+            Some((node, depth))
+        })
+    }
+
+    /// Nodes in depth-first preorder, taking out edges in the order they were added
+    pub fn dfs(&self, start: NodeId) -> impl Iterator<Item=NodeId> + '_ {
+        let mut seen = vec![false; self.node_count()];
+        let mut stack = vec![start];
+        iter::from_fn(move || {
+            loop {
+                let node = stack.pop()?;
+                if seen[node] {
+                    continue;
+                }
+                seen[node] = true;
+                let successors: Vec<_> = self.successors(node).filter(|n| !seen[*n]).collect();
+                stack.extend(successors.into_iter().rev());
+                return Some(node);
+            }
+        })
+    }
+
+    /// Indexed by node: whether it can be reached from any of `starts`
+    pub fn reachable(&self, starts: impl IntoIterator<Item=NodeId>) -> Vec<bool> {
+        let mut seen = vec![false; self.node_count()];
+        let mut stack: Vec<_> = starts.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if !seen[node] {
+                seen[node] = true;
+                stack.extend(self.successors(node).filter(|n| !seen[*n]));
+            }
+        }
+        seen
+    }
+
+    pub fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        self.bfs(from).any(|(node, _)| node == to)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E: Copy + Ord + Add<Output=E> + Default> Graph<N, E> {
+    /// Edge weights must not be negative; `E::default()` is taken as zero
+    pub fn dijkstra(&self, source: NodeId) -> ShortestPaths<E> {
+        let mut dist = vec![None; self.node_count()];
+        let mut prev = vec![None; self.node_count()];
+        let mut done = vec![false; self.node_count()];
+        dist[source] = Some(E::default());
+        let mut heap = BinaryHeap::from([(Reverse(E::default()), source)]);
+        while let Some((Reverse(d), node)) = heap.pop() {
+            if done[node] {
+                continue;
+            }
+            done[node] = true;
+            for (edge, next) in self.out_edges(node) {
+                let candidate = d + self.edge(edge).weight;
+                if dist[next].is_none_or(|current| candidate < current) {
+                    dist[next] = Some(candidate);
+                    prev[next] = Some(node);
+                    heap.push((Reverse(candidate), next));
+                }
+            }
+        }
+        ShortestPaths { source, dist, prev }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+    use super::*;
+
+    fn diamond() -> Graph<char, u32> {
+        let mut graph = Graph::new_directed();
+        for (from, to, weight) in [('a', 'b', 1), ('a', 'c', 4), ('b', 'c', 2), ('c', 'd', 1), ('b', 'd', 7)] {
+            graph.add_edge(from, to, weight);
+        }
+        graph.add_node('e');
+        graph
+    }
+
+    #[test]
+    fn test_traversals() {
+        let graph = diamond();
+        let [a, b, c, d, e] = ['a', 'b', 'c', 'd', 'e'].map(|l| graph.node_id(&l).unwrap());
+        assert_eq!(graph.bfs(a).collect_vec(), [(a, 0), (b, 1), (c, 1), (d, 2)]);
+        assert_eq!(graph.dfs(a).collect_vec(), [a, b, c, d]);
+        assert_eq!(graph.reachable([c]), [false, false, true, true, false]);
+        assert!(graph.is_reachable(a, d));
+        assert!(!graph.is_reachable(d, a));
+        assert!(!graph.is_reachable(a, e));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let graph = diamond();
+        let paths = graph.dijkstra(0);
+        let d = graph.node_id(&'d').unwrap();
+        assert_eq!(paths.dist(d), Some(4));
+        assert_eq!(paths.path_to(d).unwrap().iter().map(|n| graph.label(*n)).collect::<String>(), "abcd");
+        assert_eq!(paths.dist(graph.node_id(&'e').unwrap()), None);
+    }
+}
@@ -0,0 +1,123 @@
+use std::hash::Hash;
+use std::ops::Add;
+use super::{EdgeId, Graph, NodeId};
+
+/// The nodes of a directed cycle in order; the last has an edge back to the first
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("graph has a cycle: {0:?}")]
+pub struct CycleError(pub Vec<NodeId>);
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// Kahn's algorithm, grouped so that each layer holds the nodes whose predecessors all lie in
+    /// earlier layers. The first layer is every node without predecessors.
+    pub fn topological_layers(&self) -> Result<Vec<Vec<NodeId>>, CycleError> {
+        assert!(self.is_directed(), "topological order needs a directed graph");
+        let mut waiting_on: Vec<_> = self.nodes().map(|node| self.in_degree(node)).collect();
+        let mut layer: Vec<_> = self.nodes().filter(|node| waiting_on[*node] == 0).collect();
+        let mut layers = vec![];
+        let mut placed = 0;
+        while !layer.is_empty() {
+            placed += layer.len();
+            let mut next_layer = vec![];
+            for node in layer.iter().copied() {
+                for next in self.successors(node) {
+                    waiting_on[next] -= 1;
+                    if waiting_on[next] == 0 {
+                        next_layer.push(next);
+                    }
+                }
+            }
+            layers.push(std::mem::replace(&mut layer, next_layer));
+        }
+        if placed == self.node_count() {
+            Ok(layers)
+        } else {
+            Err(self.find_cycle(&waiting_on))
+        }
+    }
+
+    pub fn topological_order(&self) -> Result<Vec<NodeId>, CycleError> {
+        Ok(self.topological_layers()?.concat())
+    }
+
+    /// Computes a value for every node from the values of its predecessors, passed along with the
+    /// edge they arrive by
+    pub fn topological_fold<A>(
+        &self,
+        mut f: impl FnMut(NodeId, &[(EdgeId, &A)]) -> A,
+    ) -> Result<Vec<A>, CycleError> {
+        let mut values: Vec<Option<A>> = self.nodes().map(|_| None).collect();
+        for node in self.topological_order()? {
+            let incoming: Vec<_> = self.in_edges(node)
+                .map(|(edge, prev)| (edge, values[prev].as_ref().expect("predecessors come first")))
+                .collect();
+            let value = f(node, &incoming);
+            values[node] = Some(value);
+        }
+        Ok(values.into_iter().map(|value| value.expect("every node is ordered")).collect())
+    }
+
+    /// Nodes left over by Kahn's algorithm each still wait on another left-over node, so walking
+    /// backwards through them must repeat
+    fn find_cycle(&self, waiting_on: &[usize]) -> CycleError {
+        let mut on_walk = vec![None; self.node_count()];
+        let mut walk = vec![];
+        let mut node = self.nodes().find(|node| waiting_on[*node] > 0).expect("some node is left");
+        while on_walk[node].is_none() {
+            on_walk[node] = Some(walk.len());
+            walk.push(node);
+            node = self.predecessors(node)
+                .find(|prev| waiting_on[*prev] > 0)
+                .expect("left-over nodes have a left-over predecessor");
+        }
+        let mut cycle = walk.split_off(on_walk[node].expect("node repeated"));
+        cycle.reverse();
+        CycleError(cycle)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E: Copy + Ord + Add<Output=E> + Default> Graph<N, E> {
+    /// Indexed by node: the heaviest path from `source`, or None if there is no path
+    pub fn dag_longest_distances(&self, source: NodeId) -> Result<Vec<Option<E>>, CycleError> {
+        self.topological_fold(|node, incoming| {
+            if node == source {
+                return Some(E::default());
+            }
+            incoming.iter()
+                .filter_map(|(edge, dist)| Some((**dist)? + self.edge(*edge).weight))
+                .max()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_layers_and_longest() {
+        let mut graph = Graph::new_directed();
+        for (from, to, weight) in [('a', 'b', 1), ('a', 'c', 5), ('b', 'd', 1), ('c', 'd', 1), ('d', 'e', 2)] {
+            graph.add_edge(from, to, weight);
+        }
+        graph.add_edge('x', 'e', 10);
+        let [a, b, c, d, e, x] = ['a', 'b', 'c', 'd', 'e', 'x'].map(|l| graph.node_id(&l).unwrap());
+        assert_eq!(graph.topological_layers(), Ok(vec![vec![a, x], vec![b, c], vec![d], vec![e]]));
+        let longest = graph.dag_longest_distances(a).unwrap();
+        assert_eq!(longest[e], Some(8));
+        assert_eq!(longest[x], None);
+    }
+
+    #[test]
+    fn test_cycle_reported() {
+        let mut graph = Graph::new_directed();
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)] {
+            graph.add_edge(from, to, ());
+        }
+        let CycleError(cycle) = graph.topological_order().unwrap_err();
+        assert_eq!(cycle.len(), 3);
+        for (from, to) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+            assert!(graph.successors(*from).any(|next| next == *to));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Disjoint sets over the indices `0..len`, with path compression and union by size
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    /// Only meaningful at roots
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Every index starts in a set of its own
+    pub fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect(), size: vec![1; len], count: len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Adds a new index in a set of its own
+    pub fn add(&mut self) -> usize {
+        self.parent.push(self.len());
+        self.size.push(1);
+        self.count += 1;
+        self.len() - 1
+    }
+
+    /// The representative of the set holding `index`
+    pub fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = index;
+        while self.parent[current] != root {
+            current = std::mem::replace(&mut self.parent[current], root);
+        }
+        root
+    }
+
+    /// False if the two were already in the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] { (a, b) } else { (b, a) };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn component_size(&mut self, index: usize) -> usize {
+        let root = self.find(index);
+        self.size[root]
+    }
+
+    /// One entry per set, in no particular order
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|index| self.parent[*index] == *index)
+            .map(|root| self.size[root])
+            .collect()
+    }
+
+    /// The members of each set, in order of each set's smallest index
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut position = vec![None; self.len()];
+        let mut components: Vec<Vec<usize>> = vec![];
+        for index in 0..self.len() {
+            let root = self.find(index);
+            let slot = *position[root].get_or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[slot].push(index);
+        }
+        components
+    }
+}
+
+/// Disjoint sets over arbitrary keys, each added the first time it is mentioned
+#[derive(Clone, Debug)]
+pub struct KeyedUnionFind<K> {
+    ids: HashMap<K, usize>,
+    keys: Vec<K>,
+    sets: UnionFind,
+}
+
+impl<K> Default for KeyedUnionFind<K> {
+    fn default() -> Self {
+        Self { ids: HashMap::new(), keys: vec![], sets: UnionFind::new(0) }
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedUnionFind<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The key's index, adding it in a set of its own if it is new
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(id) = self.ids.get(&key) {
+            return *id;
+        }
+        let id = self.sets.add();
+        self.ids.insert(key.clone(), id);
+        self.keys.push(key);
+        id
+    }
+
+    /// The representative of the set holding `key`, or None if it was never added
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let id = *self.ids.get(key)?;
+        let root = self.sets.find(id);
+        Some(&self.keys[root])
+    }
+
+    /// Adds either key if needed; false if the two were already in the same set
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    /// Keys that were never added are only in the same set as themselves
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.ids.get(a), self.ids.get(b)) {
+            (Some(a), Some(b)) => self.sets.same(*a, *b),
+            _ => a == b,
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.sets.component_count()
+    }
+
+    /// None if the key was never added
+    pub fn component_size(&mut self, key: &K) -> Option<usize> {
+        let id = *self.ids.get(key)?;
+        Some(self.sets.component_size(id))
+    }
+
+    /// One entry per set, in no particular order
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.sets.component_sizes()
+    }
+
+    /// The members of each set, in the order they were added
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        self.sets.components().into_iter()
+            .map(|ids| ids.into_iter().map(|id| self.keys[id].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(sets.union(1, 4));
+        assert!(!sets.union(0, 3));
+        assert!(sets.same(0, 4) && !sets.same(0, 2));
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.component_size(3), 4);
+        let mut sizes = sets.component_sizes();
+        sizes.sort();
+        assert_eq!(sizes, [1, 1, 4]);
+        assert_eq!(sets.components(), [vec![0, 1, 3, 4], vec![2], vec![5]]);
+        assert_eq!(sets.add(), 6);
+        assert_eq!(sets.component_count(), 4);
+    }
+
+    #[test]
+    fn test_keyed_union_find() {
+        let mut sets = KeyedUnionFind::new();
+        sets.union("a", "b");
+        sets.union("c", "d");
+        sets.insert("e");
+        sets.union("d", "a");
+        assert!(sets.same(&"b", &"c"));
+        assert!(!sets.same(&"a", &"e") && !sets.same(&"a", &"z"));
+        assert_eq!(sets.find(&"z"), None);
+        assert_eq!(sets.component_count(), 2);
+        assert_eq!(sets.component_size(&"c"), Some(4));
+        assert_eq!(sets.components(), [vec!["a", "b", "c", "d"], vec!["e"]]);
+    }
+}
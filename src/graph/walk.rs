@@ -0,0 +1,115 @@
+use std::hash::Hash;
+use crate::number_theory::combine_congruences;
+use super::{CycleInfo, CycleShape};
+
+/// The steps at which a deterministic walk, such as a node plus an instruction index, is on a
+/// target. Hits before the cycle happen once; hits on the cycle recur every lap.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct TargetHits {
+    shape: CycleShape,
+    prefix_hits: Vec<usize>,
+    /// Steps within the first lap, from `dist_to_cycle_start` up to one lap later
+    cycle_hits: Vec<usize>,
+}
+
+impl TargetHits {
+    /// None if the walk ends without repeating a state
+    pub fn analyze<T: Hash + Eq + Clone>(
+        walk: impl IntoIterator<Item=T>,
+        mut is_target: impl FnMut(&T) -> bool,
+    ) -> Option<Self> {
+        let cycle = CycleInfo::check_cycle(walk).ok()?;
+        let start = cycle.dist_to_cycle_start();
+        let prefix_hits = cycle.prefix().iter().enumerate()
+            .filter(|(_, state)| is_target(state))
+            .map(|(step, _)| step)
+            .collect();
+        let cycle_hits = cycle.cycle().iter().enumerate()
+            .filter(|(_, state)| is_target(state))
+            .map(|(offset, _)| start + offset)
+            .collect();
+        Some(Self {
+            shape: CycleShape { dist_to_cycle_start: start, cycle_len: cycle.cycle().len() },
+            prefix_hits,
+            cycle_hits,
+        })
+    }
+
+    pub fn shape(&self) -> CycleShape {
+        self.shape
+    }
+
+    pub fn prefix_hits(&self) -> &[usize] {
+        &self.prefix_hits
+    }
+
+    pub fn cycle_hits(&self) -> &[usize] {
+        &self.cycle_hits
+    }
+
+    pub fn is_hit(&self, step: u64) -> bool {
+        let start = self.shape.dist_to_cycle_start as u64;
+        if step < start {
+            self.prefix_hits.binary_search(&(step as usize)).is_ok()
+        } else {
+            let lap_step = start + (step - start) % self.shape.cycle_len as u64;
+            self.cycle_hits.binary_search(&(lap_step as usize)).is_ok()
+        }
+    }
+}
+
+/// The first step at which every walk is on a target at once, or None if that never happens.
+/// Once all walks are cycling, every combination of cycle hits is tried, so this is meant for
+/// walks with few hits per lap.
+pub fn first_common_hit(walks: &[TargetHits]) -> Option<u64> {
+    let latest = walks.iter().max_by_key(|walk| walk.shape.dist_to_cycle_start)?;
+    // Before every walk is cycling, a common hit must be in the last walk to start cycling's prefix
+    if let Some(step) = latest.prefix_hits.iter()
+        .map(|step| *step as u64)
+        .find(|step| walks.iter().all(|walk| walk.is_hit(*step)))
+    {
+        return Some(step);
+    }
+    let start = latest.shape.dist_to_cycle_start as u64;
+    let mut combined = vec![(0, 1)];
+    for walk in walks {
+        let len = walk.shape.cycle_len as u64;
+        combined = combined.iter()
+            .flat_map(|congruence| walk.cycle_hits.iter()
+                .filter_map(|hit| combine_congruences(*congruence, (*hit as u64 % len, len))))
+            .collect();
+    }
+    combined.into_iter()
+        .map(|(residue, modulus)| {
+            // Smallest step from `start` on with this residue
+            start + (residue + modulus - start % modulus) % modulus
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_first_common_hit() {
+        // Steps 0, 1, 2, 3 then round 2, 3 forever: hits at 1 then every odd step from 3
+        let a = TargetHits::analyze([0, 1, 2, 3].into_iter().chain([2, 3].into_iter().cycle()), |n| n % 2 == 1)
+            .unwrap();
+        assert_eq!((a.prefix_hits(), a.cycle_hits()), (&[1][..], &[3][..]));
+        // Cycles through 0..5 with a hit at 4 (mod 5) only
+        let b = TargetHits::analyze((0..5).cycle(), |n| *n == 4).unwrap();
+        assert!(b.is_hit(9) && !b.is_hit(10));
+        assert_eq!(first_common_hit(&[a.clone(), b]), Some(9));
+
+        // A hit in the prefix lines up first
+        let c = TargetHits::analyze([1, 9].into_iter().chain([5, 6].into_iter().cycle()), |n| *n == 1)
+            .unwrap();
+        let d = TargetHits::analyze((1..4).cycle(), |n| *n == 1).unwrap();
+        assert_eq!(first_common_hit(&[c, d]), Some(0));
+
+        // Even steps and odd steps never meet
+        let even = TargetHits::analyze((0..2).cycle(), |n| *n == 0).unwrap();
+        assert_eq!(first_common_hit(&[even, a]), None);
+    }
+}
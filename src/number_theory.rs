@@ -1,3 +1,7 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::Rem;
+
 pub fn extended_euclidean(a: i64, b: i64) -> (i64, i64) {
     assert!(a > 0);
     assert!(b > 0);
@@ -23,11 +27,139 @@ pub fn extended_euclidean(a: i64, b: i64) -> (i64, i64) {
     (big_x, big_y)
 }
 
+pub fn gcd<T: Copy + Eq + Default + Rem<Output=T>>(mut a: T, mut b: T) -> T {
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 pub fn chinese_remainder_theorem((n_a, r_a): (i64, i64), (n_b, r_b): (i64, i64)) -> u64 {
     let (x, y) = extended_euclidean(r_a, r_b);
     (n_b * r_a * x + n_a * r_b * y).rem_euclid(r_a * r_b) as u64
 }
 
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b)).checked_mul(b).expect("lcm overflows u64")
+}
+
+/// Solves `x = r_a (mod m_a)` and `x = r_b (mod m_b)` where the moduli need not be coprime, giving
+/// the residue modulo their lcm, or None if the two conflict
+pub fn combine_congruences((r_a, m_a): (u64, u64), (r_b, m_b): (u64, u64)) -> Option<(u64, u64)> {
+    assert!(m_a > 0 && m_b > 0, "moduli must be positive");
+    let g = gcd(m_a, m_b);
+    if r_a % g != r_b % g {
+        return None;
+    }
+    let modulus = lcm(m_a, m_b);
+    // x = r_a + m_a * k, so (m_a / g) * k = (r_b - r_a) / g (mod m_b / g)
+    let m = m_b / g;
+    let k = if m == 1 {
+        0
+    } else {
+        let to_i64 = |n: u64| i64::try_from(n).expect("modulus too large");
+        let (inverse, _) = extended_euclidean(to_i64((m_a / g) % m), to_i64(m));
+        let diff = (r_b as i128 - r_a as i128) / g as i128;
+        (diff.rem_euclid(m as i128) * (inverse as i128).rem_euclid(m as i128)) % m as i128
+    };
+    let x = (r_a as i128 + m_a as i128 * k).rem_euclid(modulus as i128);
+    Some((x as u64, modulus))
+}
+
+/// Exact rational number, kept in lowest terms with a positive denominator
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Fraction {
+    num: i128,
+    den: i128,
+}
+
+impl Fraction {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert_ne!(den, 0, "zero denominator");
+        let sign = if den < 0 { -1 } else { 1 };
+        let divisor = gcd(num.unsigned_abs(), den.unsigned_abs()).max(1) as i128;
+        Self { num: sign * num / divisor, den: sign * den / divisor }
+    }
+
+    pub const fn num(&self) -> i128 {
+        self.num
+    }
+
+    pub const fn den(&self) -> i128 {
+        self.den
+    }
+
+    pub const fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub const fn is_negative(&self) -> bool {
+        self.num < 0
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl From<i128> for Fraction {
+    fn from(value: i128) -> Self {
+        Self { num: value, den: 1 }
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.num.checked_mul(other.den), other.num.checked_mul(self.den)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => compare_continued(self.num, self.den, other.num, other.den),
+        }
+    }
+}
+
+/// Compares `a / b` with `c / d` for positive `b` and `d` term by term of their continued
+/// fractions, which never overflows
+fn compare_continued(mut a: i128, mut b: i128, mut c: i128, mut d: i128) -> Ordering {
+    let mut flipped = false;
+    loop {
+        let order = a.div_euclid(b).cmp(&c.div_euclid(d))
+            .then_with(|| {
+                let (r, s) = (a.rem_euclid(b), c.rem_euclid(d));
+                // Remainders r / b and s / d compare the opposite way to b / r and d / s
+                let order = (r != 0).cmp(&(s != 0));
+                (a, b, c, d) = (b, r, d, s);
+                order
+            });
+        if order != Ordering::Equal || b == 0 || d == 0 {
+            return if flipped { order.reverse() } else { order };
+        }
+        flipped = !flipped;
+    }
+}
+
+impl Display for Fraction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
 pub fn count_combinations(n: u64, r: u64) -> u64 {
     // From Stack Overflow
     if r > n {
@@ -63,11 +195,42 @@ mod test {
         check_pair(46, 240, 2);
     }
 
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(240, 46), 2);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(7, 0), 7);
+    }
+
     #[test]
     fn test_chinese_remainder_theorem_example() {
         assert_eq!(chinese_remainder_theorem((0, 3), (3, 4)), 3);
     }
 
+    #[test]
+    fn test_combine_congruences() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(combine_congruences((3, 4), (5, 6)), Some((11, 12)));
+        assert_eq!(combine_congruences((3, 4), (4, 6)), None);
+        assert_eq!(combine_congruences((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(combine_congruences((7, 10), (2, 5)), Some((7, 10)));
+    }
+
+    #[test]
+    fn test_fraction() {
+        assert_eq!(Fraction::new(6, -4), Fraction::new(-3, 2));
+        assert_eq!(Fraction::new(-3, 2).floor(), -2);
+        assert!(Fraction::new(1, 3) < Fraction::new(1, 2));
+        assert_eq!(Fraction::new(0, -5), Fraction::from(0));
+        assert_eq!(Fraction::new(10, 4).to_string(), "5/2");
+
+        // Cross-multiplying these overflows i128
+        let big = i128::MAX / 3;
+        assert!(Fraction::new(big, big - 1) < Fraction::new(big - 1, big - 2));
+        assert!(Fraction::new(-big, big - 1) < Fraction::new(1, big));
+        assert_eq!(Fraction::new(big, 7).cmp(&Fraction::new(big, 7)), Ordering::Equal);
+    }
+
     #[test]
     fn test_count_combinations() {
         assert_eq!(count_combinations(3, 0), 1);
@@ -1,7 +1,8 @@
+use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::marker::PhantomData;
-use std::ops::{Add, Sub};
+use std::ops::{Add, Div, Mul, Sub};
 use std::str::FromStr;
 use itertools::Itertools;
 
@@ -35,6 +36,14 @@ pub trait CheckedSub<U>: Sub<U> {
     fn checked_sub(&self, v: &U) -> Option<Self::Output>;
 }
 
+pub trait CheckedMul<U>: Mul<U> {
+    fn checked_mul(&self, v: &U) -> Option<Self::Output>;
+}
+
+pub trait CheckedDiv<U>: Div<U> {
+    fn checked_div(&self, v: &U) -> Option<Self::Output>;
+}
+
 pub trait Parser {
     type Parsed<'a, 'p> where Self: 'p;
     type Err;
@@ -73,3 +82,43 @@ pub fn get_lines_from_stdin() -> io::Result<Vec<String>> {
     let reader = BufReader::with_capacity(256, stdin.lock());
     reader.lines().try_collect()
 }
+
+/// A file to write to, or stdout when `path` is None or `-`
+pub fn open_output(path: Option<&str>) -> io::Result<Box<dyn Write>> {
+    match path {
+        None | Some("-") => Ok(Box::new(io::stdout().lock())),
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+    }
+}
+
+/// Small seedable generator (xorshift64*) for randomized algorithms; not for anything
+/// security-related
+#[derive(Clone, Debug)]
+pub struct XorShift64(u64);
+
+impl XorShift64 {
+    pub fn new(seed: u64) -> Self {
+        // The all-zero state is a fixed point
+        Self(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in `0..bound`; `bound` must be positive
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0);
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}